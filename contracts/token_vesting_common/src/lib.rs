@@ -0,0 +1,91 @@
+#![no_std]
+//! Types and action hashes shared by the Token Vesting Manager contract and the contracts calling
+//! it, so that their definitions cannot drift apart.
+use soroban_sdk::{contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env, Symbol, Vec};
+
+// Tags of the action hashes, matching the storage keys and events of the actions they approve.
+const ADMINS: Symbol = symbol_short!("ADMINS");
+const ADMIN_ACCESS_SET: Symbol = symbol_short!("ADMINSET");
+const ADMIN_WITHDRAWN: Symbol = symbol_short!("ADMINWITH");
+const THRESHOLD_SET: Symbol = symbol_short!("THRESHSET");
+const UPGRADED: Symbol = symbol_short!("UPGRADED");
+const RECOVERY_SET: Symbol = symbol_short!("RECOVSET");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vesting {
+    pub recipient: Address,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub deactivation_timestamp: u64,
+    pub timelock: u64,
+    pub release_interval_secs: u64,
+    pub cliff_release_timestamp: u64,
+    pub initial_unlock: i128,
+    pub cliff_amount: i128,
+    pub linear_vest_amount: i128,
+    pub claimed_amount: i128,
+}
+
+/// Returns the action hash to approve with `propose_action` for a `withdraw_admin` call.
+pub fn withdraw_action_hash(env: &Env, caller: Address, amount_requested: i128) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(ADMIN_WITHDRAWN, caller, amount_requested).to_xdr(env))
+        .into()
+}
+
+/// Returns the action hash to approve with `propose_action` for a `set_admin` or
+/// `propose_admin_change` call.
+pub fn set_admin_action_hash(
+    env: &Env,
+    caller: Address,
+    admin: Address,
+    is_enabled: bool,
+) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(ADMIN_ACCESS_SET, caller, admin, is_enabled).to_xdr(env))
+        .into()
+}
+
+/// Returns the action hash to approve with `propose_action` for a `replace_admins` call.
+pub fn replace_admins_action_hash(
+    env: &Env,
+    caller: Address,
+    new_admins: Vec<Address>,
+) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(ADMINS, caller, new_admins).to_xdr(env))
+        .into()
+}
+
+/// Returns the action hash to approve with `propose_action` for a `set_action_threshold` call.
+pub fn threshold_action_hash(
+    env: &Env,
+    caller: Address,
+    threshold: u32,
+    window_secs: u64,
+) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(THRESHOLD_SET, caller, threshold, window_secs).to_xdr(env))
+        .into()
+}
+
+/// Returns the action hash to approve with `propose_action` for an `upgrade` or
+/// `propose_upgrade` call.
+pub fn upgrade_action_hash(env: &Env, caller: Address, new_wasm_hash: BytesN<32>) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(UPGRADED, caller, new_wasm_hash).to_xdr(env))
+        .into()
+}
+
+/// Returns the action hash to approve with `propose_action` for a `set_recovery` call.
+pub fn recovery_action_hash(
+    env: &Env,
+    caller: Address,
+    recovery: Option<Address>,
+    delay_secs: u64,
+) -> BytesN<32> {
+    env.crypto()
+        .sha256(&(RECOVERY_SET, caller, recovery, delay_secs).to_xdr(env))
+        .into()
+}
@@ -1,7 +1,10 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Val, Vec};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, vec, Address, BytesN, Env, IntoVal, Map, Symbol,
+    TryFromVal, Val, Vec,
+};
 
-/// Constants for storage keys.
+// Constants for storage keys.
 
 // Owner of the contract.
 const OWNER: Symbol = symbol_short!("OWNER");
@@ -9,12 +12,20 @@ const OWNER: Symbol = symbol_short!("OWNER");
 const WASM_HASH: Symbol = symbol_short!("WASMHASH");
 // Salt for the TokenVestingManager contract.
 const SALT: Symbol = symbol_short!("SALT");
+// List of the deployed TokenVestingManager contracts, in deployment order.
+const MANAGERS: Symbol = symbol_short!("MANAGERS");
+// Maps the deployed TokenVestingManager contracts to the Wasm hash the factory installed.
+const MANAGER_WASM_HASHES: Symbol = symbol_short!("MGRHASHES");
+// Maps tokens to the deployed TokenVestingManager contracts vesting them at deployment, in
+// deployment order.
+const MANAGERS_BY_TOKEN: Symbol = symbol_short!("MGRTOKENS");
 
-/// Constants for events.
+// Constants for events.
 
 const NEW_OWNER: Symbol = symbol_short!("NEWOWNER");
 const NEW_WASM_HASH: Symbol = symbol_short!("NEWHASH");
 const TOKEN_VESTING_MANAGER_CREATED: Symbol = symbol_short!("CREATED");
+const TOKEN_VESTING_MANAGER_UPGRADED: Symbol = symbol_short!("UPGRADED");
 
 // Minimum TTL before extending the instance lifetime: 20 days in 5 seconds ledger time
 const LIFETIME_THRESHOLD: u32 = 345_600;
@@ -33,6 +44,15 @@ impl TokenVestingFactory {
             .extend_ttl(LIFETIME_THRESHOLD, EXTENSION_AMOUNT);
     }
 
+    /// Extends the TTL for the persistent storage if it exists.
+    fn extend_persistent_ttl(e: &Env, key: Symbol) {
+        if e.storage().persistent().has(&key) {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, LIFETIME_THRESHOLD, EXTENSION_AMOUNT);
+        }
+    }
+
     /// Initialization function.
     pub fn init(env: Env, owner: Address, wasm_hash: BytesN<32>) {
         if env.storage().instance().has(&OWNER) {
@@ -50,9 +70,16 @@ impl TokenVestingFactory {
     }
 
     /// Deploys a new TokenVestingManager contract and returns its address.
+    /// `init_args` are the arguments of its `init` function: the admin, the token address, the
+    /// optional recovery address and the recovery delay.
     pub fn new_token_vesting_manager(env: Env, init_args: Vec<Val>) -> (Address, Val) {
         Self::extend_instance_ttl(&env);
 
+        let token_address = init_args
+            .get(1)
+            .and_then(|token_address| Address::try_from_val(&env, &token_address).ok())
+            .unwrap_or_else(|| panic!("Invalid init args"));
+
         let wasm_hash: BytesN<32> = env.storage().instance().get(&WASM_HASH).unwrap();
 
         let mut salt: [u8; 32] = env.storage().instance().get(&SALT).unwrap();
@@ -74,15 +101,42 @@ impl TokenVestingFactory {
         let deployed_address = env
             .deployer()
             .with_address(env.current_contract_address(), new_salt)
-            .deploy_v2(wasm_hash, Vec::<Val>::new(&env));
+            .deploy_v2(wasm_hash.clone(), Vec::<Val>::new(&env));
 
         // Invoke the init function with the given arguments.
         let res: Val = env.invoke_contract(&deployed_address, &symbol_short!("init"), init_args);
 
+        // Record the deployed contract in the registry.
+        Self::extend_persistent_ttl(&env, MANAGERS);
+        let mut managers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&MANAGERS)
+            .unwrap_or_else(|| Vec::new(&env));
+        managers.push_back(deployed_address.clone());
+        env.storage().persistent().set(&MANAGERS, &managers);
+        Self::set_manager_wasm_hash(&env, deployed_address.clone(), wasm_hash);
+
+        Self::extend_persistent_ttl(&env, MANAGERS_BY_TOKEN);
+        let mut managers_by_token: Map<Address, Vec<Address>> = env
+            .storage()
+            .persistent()
+            .get(&MANAGERS_BY_TOKEN)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut token_managers: Vec<Address> = managers_by_token
+            .get(token_address.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        token_managers.push_back(deployed_address.clone());
+        managers_by_token.set(token_address, token_managers);
+        env.storage()
+            .persistent()
+            .set(&MANAGERS_BY_TOKEN, &managers_by_token);
+
         env.events()
             .publish((TOKEN_VESTING_MANAGER_CREATED,), deployed_address.clone());
 
-        // Return the contract ID of the deployed contract and the result data of invoking the `init` result.
+        // Return the contract ID of the deployed contract and the result data of invoking the `init` result,
+        // which is the address of the deployed TokenVestingManager contract.
         (deployed_address, res)
     }
 
@@ -126,6 +180,38 @@ impl TokenVestingFactory {
         env.events().publish((NEW_WASM_HASH,), new_wasm_hash);
     }
 
+    /// Upgrades a deployed TokenVestingManager contract to the current Wasm hash of the factory.
+    /// The owner must also be an admin of the manager.
+    pub fn upgrade_manager(env: Env, caller: Address, manager: Address) {
+        Self::extend_instance_ttl(&env);
+
+        let owner: Address = env.storage().instance().get(&OWNER).unwrap();
+
+        // Access control check
+        caller.require_auth();
+        if caller != owner {
+            panic!("Not the owner");
+        }
+
+        let wasm_hash: BytesN<32> = env.storage().instance().get(&WASM_HASH).unwrap();
+
+        assert!(
+            Self::get_manager_wasm_hash(env.clone(), manager.clone()) != wasm_hash,
+            "Manager already up to date"
+        );
+
+        env.invoke_contract::<()>(
+            &manager,
+            &symbol_short!("upgrade"),
+            vec![&env, caller.into_val(&env), wasm_hash.into_val(&env)],
+        );
+
+        Self::set_manager_wasm_hash(&env, manager.clone(), wasm_hash.clone());
+
+        env.events()
+            .publish((TOKEN_VESTING_MANAGER_UPGRADED,), (manager, wasm_hash));
+    }
+
     /// Returns the owner of the factory.
     pub fn get_owner(env: Env) -> Address {
         Self::extend_instance_ttl(&env);
@@ -139,6 +225,85 @@ impl TokenVestingFactory {
 
         env.storage().instance().get(&WASM_HASH).unwrap()
     }
+
+    /// Returns the owner, the TokenVestingManager Wasm hash and the current deployment salt.
+    pub fn get_config(env: Env) -> (Address, BytesN<32>, BytesN<32>) {
+        Self::extend_instance_ttl(&env);
+
+        (
+            env.storage().instance().get(&OWNER).unwrap(),
+            env.storage().instance().get(&WASM_HASH).unwrap(),
+            env.storage().instance().get(&SALT).unwrap(),
+        )
+    }
+
+    /// Returns the address of a deployed TokenVestingManager contract by its deployment order.
+    pub fn get_manager_by_index(env: Env, index: u32) -> Address {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, MANAGERS);
+
+        let managers: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&MANAGERS)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        managers
+            .get(index)
+            .unwrap_or_else(|| panic!("Manager index out of range"))
+    }
+
+    /// Returns the deployed TokenVestingManager contracts that were deployed to vest the given
+    /// token, in deployment order. This reflects the token as deployed by the factory: a manager
+    /// whose token is later changed with its `reconfigure_token` is still listed under its
+    /// original token.
+    pub fn get_managers_for_token(env: Env, token: Address) -> Vec<Address> {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, MANAGERS_BY_TOKEN);
+
+        let managers_by_token: Map<Address, Vec<Address>> = env
+            .storage()
+            .persistent()
+            .get(&MANAGERS_BY_TOKEN)
+            .unwrap_or_else(|| Map::new(&env));
+
+        managers_by_token
+            .get(token)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns the Wasm hash the factory deployed or last upgraded a TokenVestingManager contract
+    /// to. An upgrade made by the manager's own admins through its `upgrade` function is not
+    /// reflected here.
+    pub fn get_manager_wasm_hash(env: Env, manager: Address) -> BytesN<32> {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, MANAGER_WASM_HASHES);
+
+        let manager_wasm_hashes: Map<Address, BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&MANAGER_WASM_HASHES)
+            .unwrap_or_else(|| Map::new(&env));
+
+        manager_wasm_hashes
+            .get(manager)
+            .unwrap_or_else(|| panic!("Unknown manager"))
+    }
+
+    /// Records the Wasm hash a deployed TokenVestingManager contract runs.
+    fn set_manager_wasm_hash(env: &Env, manager: Address, wasm_hash: BytesN<32>) {
+        let mut manager_wasm_hashes: Map<Address, BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&MANAGER_WASM_HASHES)
+            .unwrap_or_else(|| Map::new(env));
+
+        manager_wasm_hashes.set(manager, wasm_hash);
+        env.storage()
+            .persistent()
+            .set(&MANAGER_WASM_HASHES, &manager_wasm_hashes);
+        Self::extend_persistent_ttl(env, MANAGER_WASM_HASHES);
+    }
 }
 
 mod test;
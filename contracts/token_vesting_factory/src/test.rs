@@ -9,7 +9,15 @@ mod token_vesting_manager_wasm {
 }
 
 use super::*;
-use soroban_sdk::{bytesn, testutils::Address as TestAddress, vec, BytesN, Env};
+use soroban_sdk::{
+    bytesn, testutils::Address as TestAddress, vec, BytesN, Env, IntoVal, TryFromVal,
+};
+
+#[test]
+fn test_manager_wasm_size() {
+    // Larger Wasm exceeds the network limit on contract code size.
+    assert!(token_vesting_manager_wasm::WASM.len() <= 128 * 1024);
+}
 
 #[test]
 #[should_panic]
@@ -44,7 +52,18 @@ fn test_deploy_token_vesting_manager_contract_from_factory() {
     let factory_caller = Address::generate(&env);
     let token_address = Address::generate(&env);
 
-    client.new_token_vesting_manager(&vec![&env, factory_caller.to_val(), token_address.to_val()]);
+    let (deployed_address, init_result) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        token_address.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+
+    assert_eq!(
+        Address::try_from_val(&env, &init_result).unwrap(),
+        deployed_address
+    );
 }
 
 #[test]
@@ -175,3 +194,208 @@ fn test_get_vesting_manager_wasm_hash() {
 
     assert_eq!(client.get_vesting_manager_wasm_hash(), wasm_hash);
 }
+
+#[test]
+fn test_get_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(TokenVestingFactory, ());
+    let client = TokenVestingFactoryClient::new(&env, &contract_id);
+
+    let owner: Address = Address::generate(&env);
+    let wasm_hash: BytesN<32> = bytesn!(
+        &env,
+        0x89424fc9ff1cf53ab622eb1616ebe19ad3815d9d139736ec2a2d59e75b075c60
+    );
+
+    client.init(&owner, &wasm_hash);
+
+    assert_eq!(
+        client.get_config(),
+        (
+            client.get_owner(),
+            client.get_vesting_manager_wasm_hash(),
+            BytesN::from_array(&env, &[0; 32])
+        )
+    );
+
+    let new_owner: Address = Address::generate(&env);
+    client.update_owner(&owner, &new_owner);
+
+    let (config_owner, config_wasm_hash, _) = client.get_config();
+    assert_eq!(config_owner, new_owner);
+    assert_eq!(config_wasm_hash, wasm_hash);
+}
+
+#[test]
+fn test_get_manager_by_index() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingFactory, ());
+    let client = TokenVestingFactoryClient::new(&env, &contract_id);
+
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(token_vesting_manager_wasm::WASM);
+
+    let owner: Address = Address::generate(&env);
+
+    client.init(&owner, &wasm_hash);
+
+    let factory_caller = Address::generate(&env);
+    let token_address = Address::generate(&env);
+
+    let (first_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        token_address.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+    let (second_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        token_address.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+
+    assert_eq!(client.get_manager_by_index(&0), first_address);
+    assert_eq!(client.get_manager_by_index(&1), second_address);
+    assert!(client.try_get_manager_by_index(&2).is_err());
+}
+
+#[test]
+fn test_get_managers_for_token() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingFactory, ());
+    let client = TokenVestingFactoryClient::new(&env, &contract_id);
+
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(token_vesting_manager_wasm::WASM);
+
+    let owner: Address = Address::generate(&env);
+
+    client.init(&owner, &wasm_hash);
+
+    let factory_caller = Address::generate(&env);
+    let first_token = Address::generate(&env);
+    let second_token = Address::generate(&env);
+
+    let (first_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        first_token.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+    let (second_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        second_token.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+    let (third_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        factory_caller.to_val(),
+        first_token.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+
+    assert_eq!(
+        client.get_managers_for_token(&first_token),
+        vec![&env, first_address, third_address]
+    );
+    assert_eq!(
+        client.get_managers_for_token(&second_token),
+        vec![&env, second_address]
+    );
+    assert!(client
+        .get_managers_for_token(&Address::generate(&env))
+        .is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_get_manager_by_index_out_of_range() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingFactory, ());
+    let client = TokenVestingFactoryClient::new(&env, &contract_id);
+
+    let owner: Address = Address::generate(&env);
+    let wasm_hash: BytesN<32> = BytesN::from_array(&env, &[0; 32]);
+
+    client.init(&owner, &wasm_hash);
+
+    // Panics because no manager has been deployed yet.
+    client.get_manager_by_index(&0);
+}
+
+#[test]
+fn test_upgrade_manager() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(TokenVestingFactory, ());
+    let client = TokenVestingFactoryClient::new(&env, &contract_id);
+
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(token_vesting_manager_wasm::WASM);
+
+    // A second version of the manager: the same code with an extra custom section.
+    let mut new_wasm = soroban_sdk::Bytes::from_slice(&env, token_vesting_manager_wasm::WASM);
+    new_wasm.append(&soroban_sdk::Bytes::from_slice(
+        &env,
+        &[0x00, 0x03, 0x02, b'v', b'2'],
+    ));
+    let new_wasm_hash = env.deployer().upload_contract_wasm(new_wasm);
+    assert_ne!(wasm_hash, new_wasm_hash);
+
+    let owner: Address = Address::generate(&env);
+
+    client.init(&owner, &wasm_hash);
+
+    let token_address = Address::generate(&env);
+
+    // The owner deploys the manager, so it is also its admin.
+    let (manager_address, _) = client.new_token_vesting_manager(&vec![
+        &env,
+        owner.to_val(),
+        token_address.to_val(),
+        None::<Address>.into_val(&env),
+        0_u64.into_val(&env),
+    ]);
+
+    assert_eq!(client.get_manager_wasm_hash(&manager_address), wasm_hash);
+
+    // Nothing to upgrade while the factory hash is unchanged.
+    assert!(client
+        .try_upgrade_manager(&owner, &manager_address)
+        .is_err());
+
+    client.update_vesting_manager_wasm_hash(&owner, &new_wasm_hash);
+
+    // Only the owner can upgrade a manager.
+    let not_owner = Address::generate(&env);
+    assert!(client
+        .try_upgrade_manager(&not_owner, &manager_address)
+        .is_err());
+
+    client.upgrade_manager(&owner, &manager_address);
+
+    assert_eq!(
+        client.get_manager_wasm_hash(&manager_address),
+        new_wasm_hash
+    );
+
+    // The upgraded manager keeps its state.
+    let manager_client = token_vesting_manager_wasm::Client::new(&env, &manager_address);
+    assert_eq!(manager_client.get_token_address(), token_address);
+
+    // Unknown managers have no tracked version.
+    assert!(client
+        .try_get_manager_wasm_hash(&Address::generate(&env))
+        .is_err());
+}
@@ -1,10 +1,17 @@
 #![no_std]
+// Entrypoint arguments are the contract interface.
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token::TokenClient, Address, Env, Map,
-    Symbol, Vec,
+    contract, contractimpl, contracttype, symbol_short, token::TokenClient, vec, Address, BytesN,
+    Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec,
+};
+pub use token_vesting_common::Vesting;
+use token_vesting_common::{
+    recovery_action_hash, replace_admins_action_hash, set_admin_action_hash, threshold_action_hash,
+    upgrade_action_hash, withdraw_action_hash,
 };
 
-/// Constants for storage keys.
+// Constants for storage keys.
 
 // Maps the admins of the contract.
 const ADMINS: Symbol = symbol_short!("ADMINS");
@@ -22,31 +29,144 @@ const VESTING_BY_ID: Symbol = symbol_short!("VBYID");
 const NONCE: Symbol = symbol_short!("NONCE");
 // List of all recipients.
 const RECIPIENTS: Symbol = symbol_short!("RECIPS");
-
-/// Constants for events.
-
+// Number of distinct admin approvals required for sensitive admin actions.
+const ACTION_THRESHOLD: Symbol = symbol_short!("THRESHOLD");
+// Number of seconds an admin approval remains valid.
+const APPROVAL_WINDOW: Symbol = symbol_short!("APPWINDOW");
+// Maps the admin approvals (admin to approval timestamp) for each action hash.
+const ACTION_APPROVALS: Symbol = symbol_short!("APPROVALS");
+// Number of seconds after the end of a vesting before its unclaimed tokens can be reclaimed.
+const RECLAIM_GRACE_SECS: Symbol = symbol_short!("RECLGRACE");
+// Minimum number of seconds between two claims of the same vesting.
+const CLAIM_COOLDOWN: Symbol = symbol_short!("CLAIMCOOL");
+// Maps the timestamp of the last claim for each vesting id.
+const LAST_CLAIM_TIMESTAMPS: Symbol = symbol_short!("LASTCLAIM");
+// Maps the paused flag for each vesting id.
+const PAUSED_VESTINGS: Symbol = symbol_short!("VPAUSED");
+// Whether a release interval spanning the whole linear vesting duration is allowed.
+const SINGLE_INTERVAL_ALLOWED: Symbol = symbol_short!("SINGLEINT");
+// Maps the number of seconds over which the cliff amount vests linearly for each vesting id.
+const CLIFF_RAMPS: Symbol = symbol_short!("CLIFFRAMP");
+// Number of seconds between requesting and executing an admin withdrawal.
+const WITHDRAW_DELAY: Symbol = symbol_short!("WDELAY");
+// Pending decrease of the withdrawal delay and the timestamp from which it can be applied.
+const PENDING_WITHDRAW_DELAY: Symbol = symbol_short!("PENDWDLY");
+// Pending admin withdrawal waiting for its delay to elapse.
+const PENDING_WITHDRAW: Symbol = symbol_short!("PENDWITH");
+// Whether recipients must acknowledge their vesting before claiming.
+const REQUIRE_ACK: Symbol = symbol_short!("REQACK");
+// Maps the acknowledged flag for each vesting id.
+const ACKNOWLEDGED_VESTINGS: Symbol = symbol_short!("VACKED");
+// Whether claims are paused for all vestings.
+const CLAIMS_PAUSED: Symbol = symbol_short!("CPAUSED");
+// Reason given for the global claims pause.
+const PAUSE_REASON: Symbol = symbol_short!("PREASON");
+// Maximum number of vestings per recipient.
+const MAX_VESTINGS_PER_RECIPIENT: Symbol = symbol_short!("MAXPERREC");
+// Release interval used by `create_vesting_default_interval` of the operator contract.
+const DEFAULT_INTERVAL: Symbol = symbol_short!("DEFINTVL");
+// Maps recipients to the hook contract called after each of their claims.
+const CLAIM_HOOKS: Symbol = symbol_short!("CLAIMHOOK");
+// Whether only allowlisted recipients can receive new vestings.
+const RECIPIENT_ALLOWLIST_ENABLED: Symbol = symbol_short!("ALLOWLIST");
+// Maps the recipients allowed to receive new vestings while the allowlist is enabled.
+const RECIPIENT_ALLOWLIST: Symbol = symbol_short!("RALLOWED");
+// Maps vesting ids to the address that funded them.
+const VESTING_FUNDERS: Symbol = symbol_short!("VFUNDERS");
+// Whether the contract is sunset, permanently blocking the creation of vestings.
+const SUNSET: Symbol = symbol_short!("SUNSET");
+// Storage layout version recorded by the last migration.
+const STORAGE_VERSION: Symbol = symbol_short!("STORVER");
+// Unvested amount above which revoking a vesting requires the approval of a second admin.
+const REVOKE_APPROVAL_THRESHOLD: Symbol = symbol_short!("REVTHRESH");
+// Proposed revoke approval threshold that weakens the current one, and the admin proposing it.
+const PENDING_REVOKE_THRESHOLD: Symbol = symbol_short!("PENDRVTH");
+// Maps vesting ids to the admin who proposed their revocation.
+const PENDING_REVOCATIONS: Symbol = symbol_short!("PENDREV");
+// Maps recipients to the address their claimed tokens must be sent to.
+const MANDATORY_DESTINATIONS: Symbol = symbol_short!("MANDEST");
+// Recent admin changes (timestamp, acting admin, target admin, enabled), oldest first.
+const ADMIN_HISTORY: Symbol = symbol_short!("ADMHIST");
+// Minimum number of seconds between the start and the end of a new vesting.
+const MIN_DURATION_SECS: Symbol = symbol_short!("MINDURSEC");
+// Maximum number of seconds the start of a new vesting can be ahead of the ledger time.
+const MAX_FUTURE_START: Symbol = symbol_short!("MAXFUTURE");
+// Maps the ids of the vestings which cannot be revoked.
+const IRREVOCABLE_VESTINGS: Symbol = symbol_short!("IRREVOC");
+// Number of seconds between proposing and executing an admin change.
+const ADMIN_CHANGE_DELAY: Symbol = symbol_short!("ADMDELAY");
+// Pending decrease of the admin change delay and the timestamp from which it can be applied.
+const PENDING_ADMIN_CHANGE_DELAY: Symbol = symbol_short!("PENDADLY");
+// Pending admin change waiting for its delay to elapse.
+const PENDING_ADMIN_CHANGE: Symbol = symbol_short!("PENDADMIN");
+// Maps recipients to the running total paid out to them across their vestings.
+const RECIPIENT_CLAIMED: Symbol = symbol_short!("RCLAIMED");
+// Whether admins are barred from being vesting recipients.
+const BLOCK_ADMIN_RECIPIENTS: Symbol = symbol_short!("BLKADMREC");
+// Backup address allowed to take over the admin set after a period of admin inactivity.
+const RECOVERY_ADDRESS: Symbol = symbol_short!("RECOVERY");
+// Number of seconds without admin activity after which the recovery address can take over.
+const RECOVERY_DELAY: Symbol = symbol_short!("RECDELAY");
+// Timestamp of the most recent admin action.
+const LAST_ADMIN_ACTION: Symbol = symbol_short!("LASTADMIN");
+// Proposed Wasm hash and the timestamp from which `upgrade` can install it.
+const PENDING_UPGRADE: Symbol = symbol_short!("PENDUPGR");
+
+// Constants for events.
+// Vesting lifecycle events (created, claimed, revoked, drained, purged) are published with
+// the recipient and the vesting id as additional topics.
+
+const INITIALIZED: Symbol = symbol_short!("INIT");
 const ADMIN_ACCESS_SET: Symbol = symbol_short!("ADMINSET");
 const VESTING_CREATED: Symbol = symbol_short!("VCREATED");
 const CLAIMED: Symbol = symbol_short!("CLAIMED");
 const VESTING_REVOKED: Symbol = symbol_short!("VREVOKED");
 const ADMIN_WITHDRAWN: Symbol = symbol_short!("ADMINWITH");
 const ADMIN_WITHDRAWN_OTHER: Symbol = symbol_short!("WITHOTHER");
-
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Vesting {
-    pub recipient: Address,
-    pub start_timestamp: u64,
-    pub end_timestamp: u64,
-    pub deactivation_timestamp: u64,
-    pub timelock: u64,
-    pub release_interval_secs: u64,
-    pub cliff_release_timestamp: u64,
-    pub initial_unlock: i128,
-    pub cliff_amount: i128,
-    pub linear_vest_amount: i128,
-    pub claimed_amount: i128,
-}
+const THRESHOLD_SET: Symbol = symbol_short!("THRESHSET");
+const ACTION_APPROVED: Symbol = symbol_short!("APPROVED");
+const BATCH_SKIPPED: Symbol = symbol_short!("BSKIPPED");
+const CONFIG_SET: Symbol = symbol_short!("CONFIGSET");
+const VESTING_DRAINED: Symbol = symbol_short!("VDRAINED");
+const VESTING_PAUSE_SET: Symbol = symbol_short!("VPAUSESET");
+const DEPOSITED: Symbol = symbol_short!("DEPOSITED");
+const VESTING_PURGED: Symbol = symbol_short!("VPURGED");
+const CLIFF_RAMP_SET: Symbol = symbol_short!("RAMPSET");
+const WITHDRAW_REQUESTED: Symbol = symbol_short!("WREQUEST");
+const WITHDRAW_CANCELLED: Symbol = symbol_short!("WCANCEL");
+const VESTING_ACKNOWLEDGED: Symbol = symbol_short!("VESTACKED");
+const CLAIMS_PAUSE_SET: Symbol = symbol_short!("CPAUSESET");
+const CLAIMED_AMOUNT_SET: Symbol = symbol_short!("CLAIMSET");
+const CLAIM_HOOK_SET: Symbol = symbol_short!("HOOKSET");
+const RECIPIENT_ALLOWED_SET: Symbol = symbol_short!("RALLOWSET");
+const SUNSET_SET: Symbol = symbol_short!("SUNSETSET");
+const REVOCATION_PROPOSED: Symbol = symbol_short!("REVPROP");
+const MANDATORY_DESTINATION_SET: Symbol = symbol_short!("DESTSET");
+const TOKEN_RECONFIGURED: Symbol = symbol_short!("TOKENRECF");
+const UPGRADED: Symbol = symbol_short!("UPGRADED");
+const MIGRATED: Symbol = symbol_short!("MIGRATED");
+const UPGRADE_PROPOSED: Symbol = symbol_short!("UPGPROP");
+const ADMIN_CHANGE_PROPOSED: Symbol = symbol_short!("ADMPROP");
+const ADMIN_CHANGE_CANCELLED: Symbol = symbol_short!("ADMCANCEL");
+const REVOKE_REFUNDED: Symbol = symbol_short!("REFUNDED");
+const VESTING_SPLIT: Symbol = symbol_short!("VSPLIT");
+const VESTING_TRANSFERRED: Symbol = symbol_short!("VTRANSFER");
+const RECOVERY_SET: Symbol = symbol_short!("RECOVSET");
+const RECOVERED: Symbol = symbol_short!("RECOVERED");
+
+// Storage layout version this code expects. Bump it together with the data fixes in `migrate`.
+const CURRENT_STORAGE_VERSION: u32 = 1;
+
+// Reason code for a batch entry skipped because the vesting is still timelocked.
+const SKIP_TIMELOCKED: u32 = 1;
+// Reason code for a batch entry skipped because the vesting has nothing to claim.
+const SKIP_NOTHING_TO_CLAIM: u32 = 2;
+// Reason code for a batch entry skipped because the vesting claim cooldown is active.
+const SKIP_COOLDOWN: u32 = 3;
+// Reason code for a batch entry skipped because the vesting is paused.
+const SKIP_PAUSED: u32 = 4;
+// Reason code for a batch entry skipped because the vesting has not been acknowledged.
+const SKIP_NOT_ACKNOWLEDGED: u32 = 5;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -62,6 +182,34 @@ pub struct CreateVestingBatchParams {
     pub linear_vest_amounts: Vec<i128>,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingWithdrawal {
+    pub requester: Address,
+    pub amount: i128,
+    pub ready_timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdminChange {
+    pub proposer: Address,
+    pub admin: Address,
+    pub is_enabled: bool,
+    pub ready_timestamp: u64,
+}
+
+// Maximum number of admin changes kept in `ADMIN_HISTORY`.
+const ADMIN_HISTORY_CAP: u32 = 20;
+
+// Hard limit of vestings per recipient, bounding the loops over the vestings of a recipient.
+const MAX_RECIPIENT_VESTINGS: u32 = 100;
+// Maximum number of recipients returned by a single call of a recipient list view.
+const MAX_RECIPIENTS_PAGE: u32 = 100;
+
+// Minimum admin inactivity delay before the recovery address can take over: 30 days.
+const MIN_RECOVERY_DELAY_SECS: u64 = 2_592_000;
+
 // Minimum TTL before extending the storage lifetime: 20 days in 5 seconds ledger time
 const LIFETIME_THRESHOLD: u32 = 345_600;
 // Extension amount for the storage lifetime: 30 days in 5 seconds ledger time
@@ -86,8 +234,15 @@ impl TokenVestingManager {
             .extend_ttl(&key, LIFETIME_THRESHOLD, EXTENSION_AMOUNT);
     }
 
-    /// Initialization function.
-    pub fn init(env: Env, factory_caller: Address, token_address: Address) {
+    /// Initialization function, optionally setting the recovery address and the admin inactivity
+    /// delay after which it can call `claim_recovery`. Returns the address of the contract.
+    pub fn init(
+        env: Env,
+        factory_caller: Address,
+        token_address: Address,
+        recovery: Option<Address>,
+        recovery_delay_secs: u64,
+    ) -> Address {
         if env.storage().persistent().has(&ADMINS) {
             panic!("Already initialized");
         }
@@ -96,7 +251,7 @@ impl TokenVestingManager {
         admins.set(factory_caller.clone(), true);
         env.storage().persistent().set(&ADMINS, &admins);
         env.events()
-            .publish((ADMIN_ACCESS_SET,), (factory_caller, true));
+            .publish((ADMIN_ACCESS_SET,), (factory_caller.clone(), true));
 
         let admin_count: u32 = 1;
         env.storage().instance().set(&ADMIN_COUNT, &admin_count);
@@ -116,368 +271,375 @@ impl TokenVestingManager {
         env.storage()
             .instance()
             .set(&TOKENS_RESERVED_FOR_VESTING, &0_i128);
+        env.storage()
+            .instance()
+            .set(&LAST_ADMIN_ACTION, &env.ledger().timestamp());
+        env.storage()
+            .instance()
+            .set(&STORAGE_VERSION, &CURRENT_STORAGE_VERSION);
+        if recovery.is_some() {
+            Self::store_recovery(&env, recovery, recovery_delay_secs);
+        }
 
         // Set initial TTL
         Self::extend_instance_ttl(&env);
         Self::extend_persistent_ttl(&env, ADMINS);
-    }
 
-    /// Adds a new admin or remove an existing one for the Token Vesting Manager contract.
-    pub fn set_admin(env: Env, caller: Address, admin: Address, is_enabled: bool) {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, ADMINS);
+        env.events().publish(
+            (INITIALIZED,),
+            (token_address, Vec::from_array(&env, [factory_caller])),
+        );
 
-        let mut admins: Map<Address, bool> = env
-            .storage()
-            .persistent()
-            .get(&ADMINS)
-            .unwrap_or_else(|| Map::new(&env));
+        env.current_contract_address()
+    }
 
+    /// Upgrades the contract code to an uploaded Wasm hash, proposed with `propose_upgrade` unless
+    /// the admin change delay is 0.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
         // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
-
-        assert!(
-            admins.get(admin.clone()).unwrap_or(false) != is_enabled,
-            "Flag provided already set"
-        );
-
-        let admin_count: u32 = env.storage().instance().get(&ADMIN_COUNT).unwrap_or(0);
+        Self::require_admin(&env, &caller);
 
-        if is_enabled {
-            let new_admin_count: u32 = admin_count + 1;
-            env.storage().instance().set(&ADMIN_COUNT, &new_admin_count);
+        if Self::get_admin_change_delay(env.clone()) == 0 {
+            Self::consume_action_approvals(
+                env.clone(),
+                upgrade_action_hash(&env, caller.clone(), new_wasm_hash.clone()),
+            );
         } else {
-            assert!(admin_count > 1, "There must always be at least 1 admin");
-            let new_admin_count: u32 = admin_count - 1;
-            env.storage().instance().set(&ADMIN_COUNT, &new_admin_count);
+            let (pending_wasm_hash, ready_timestamp) = Self::get_pending_upgrade(env.clone());
+            assert!(pending_wasm_hash == new_wasm_hash, "Upgrade not proposed");
+            assert!(
+                env.ledger().timestamp() >= ready_timestamp,
+                "Admin change delay not elapsed"
+            );
         }
+        env.storage().instance().remove(&PENDING_UPGRADE);
 
-        admins.set(admin.clone(), is_enabled);
-        env.storage().persistent().set(&ADMINS, &admins);
-        env.events()
-            .publish((ADMIN_ACCESS_SET,), (admin, is_enabled));
-    }
-
-    /// Returns the number of admins for the Token Vesting Manager contract.
-    pub fn get_admins_count(env: Env) -> u32 {
-        Self::extend_instance_ttl(&env);
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
 
-        env.storage().instance().get(&ADMIN_COUNT).unwrap_or(0)
+        env.events().publish((UPGRADED,), new_wasm_hash);
     }
 
-    /// Returns true if the given address is an admin, false otherwise.
-    pub fn is_admin(env: Env, address: Address) -> bool {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, ADMINS);
+    /// Proposes to upgrade the contract code to the given Wasm hash once the admin change delay has
+    /// elapsed.
+    pub fn propose_upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        // Access control check
+        Self::require_admin(&env, &caller);
 
-        let admins: Map<Address, bool> = env
-            .storage()
-            .persistent()
-            .get(&ADMINS)
-            .unwrap_or_else(|| Map::new(&env));
+        Self::consume_action_approvals(
+            env.clone(),
+            upgrade_action_hash(&env, caller.clone(), new_wasm_hash.clone()),
+        );
 
-        admins.get(address).unwrap_or(false)
+        let ready_timestamp = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_admin_change_delay(env.clone()));
+        env.storage()
+            .instance()
+            .set(&PENDING_UPGRADE, &(new_wasm_hash.clone(), ready_timestamp));
+
+        env.events().publish(
+            (UPGRADE_PROPOSED,),
+            (caller, new_wasm_hash, ready_timestamp),
+        );
     }
 
-    /// Creates a vesting schedule for a recipient and returns a vesting ID.
-    pub fn create_vesting(
-        env: Env,
-        caller: Address,
-        recipient: Address,
-        start_timestamp: u64,
-        end_timestamp: u64,
-        timelock: u64,
-        initial_unlock: i128,
-        cliff_release_timestamp: u64,
-        cliff_amount: i128,
-        release_interval_secs: u64,
-        linear_vest_amount: i128,
-    ) -> u64 {
+    /// Returns the pending upgrade Wasm hash and the timestamp from which it can be installed.
+    pub fn get_pending_upgrade(env: Env) -> (BytesN<32>, u64) {
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, ADMINS);
 
-        let admins: Map<Address, bool> = env.storage().persistent().get(&ADMINS).unwrap();
+        env.storage()
+            .instance()
+            .get(&PENDING_UPGRADE)
+            .unwrap_or_else(|| panic!("No pending upgrade"))
+    }
 
+    /// Applies the data fixes needed after an upgrade and records the new storage version.
+    pub fn migrate(env: Env, caller: Address) {
         // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
+        Self::require_admin(&env, &caller);
 
-        Self::create_vesting_internal(
-            env.clone(),
-            caller.clone(),
-            recipient.clone(),
-            start_timestamp,
-            end_timestamp,
-            timelock,
-            initial_unlock,
-            cliff_release_timestamp,
-            cliff_amount,
-            release_interval_secs,
-            linear_vest_amount,
-        )
+        let from_version = Self::get_storage_version(env.clone());
+        assert!(from_version < CURRENT_STORAGE_VERSION, "Already migrated");
+
+        // Version 0 to 1 has no data changes, it only starts tracking the version.
+
+        env.storage()
+            .instance()
+            .set(&STORAGE_VERSION, &CURRENT_STORAGE_VERSION);
+
+        env.events()
+            .publish((MIGRATED,), (from_version, CURRENT_STORAGE_VERSION));
     }
 
-    /// Creates vesting schedules in batch for multiple recipients.
-    pub fn create_vesting_batch(
-        env: Env,
-        caller: Address,
-        create_vesting_batch_params: CreateVestingBatchParams,
-    ) -> Vec<u64> {
+    /// Returns the storage version recorded at initialization or by the last migration.
+    pub fn get_storage_version(env: Env) -> u32 {
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, ADMINS);
 
-        let admins: Map<Address, bool> = env
-            .storage()
-            .persistent()
-            .get(&ADMINS)
-            .unwrap_or_else(|| Map::new(&env));
+        env.storage().instance().get(&STORAGE_VERSION).unwrap_or(0)
+    }
+
+    /// Returns the storage version this code expects and the one recorded by the last migration.
+    pub fn migration_status(env: Env) -> (u32, u32) {
+        (CURRENT_STORAGE_VERSION, Self::get_storage_version(env))
+    }
 
+    /// Adds a new admin or remove an existing one for the Token Vesting Manager contract.
+    pub fn set_admin(env: Env, caller: Address, admin: Address, is_enabled: bool) {
         // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
+        Self::require_admin(&env, &caller);
 
-        let length: u32 = create_vesting_batch_params.recipients.len();
         assert!(
-            create_vesting_batch_params.start_timestamps.len() == length
-                && create_vesting_batch_params.end_timestamps.len() == length
-                && create_vesting_batch_params.timelocks.len() == length
-                && create_vesting_batch_params.initial_unlocks.len() == length
-                && create_vesting_batch_params.cliff_release_timestamps.len() == length
-                && create_vesting_batch_params.cliff_amounts.len() == length
-                && create_vesting_batch_params.release_interval_secs.len() == length
-                && create_vesting_batch_params.linear_vest_amounts.len() == length,
-            "Array length mismatch"
+            Self::get_admin_change_delay(env.clone()) == 0,
+            "Admin change delay active"
         );
 
-        let mut vesting_ids: Vec<u64> = Vec::new(&env);
-
-        for i in 0..length {
-            vesting_ids.insert(
-                i,
-                Self::create_vesting_internal(
-                    env.clone(),
-                    caller.clone(),
-                    create_vesting_batch_params.recipients.get(i).unwrap(),
-                    create_vesting_batch_params.start_timestamps.get(i).unwrap(),
-                    create_vesting_batch_params.end_timestamps.get(i).unwrap(),
-                    create_vesting_batch_params.timelocks.get(i).unwrap(),
-                    create_vesting_batch_params.initial_unlocks.get(i).unwrap(),
-                    create_vesting_batch_params
-                        .cliff_release_timestamps
-                        .get(i)
-                        .unwrap(),
-                    create_vesting_batch_params.cliff_amounts.get(i).unwrap(),
-                    create_vesting_batch_params
-                        .release_interval_secs
-                        .get(i)
-                        .unwrap(),
-                    create_vesting_batch_params
-                        .linear_vest_amounts
-                        .get(i)
-                        .unwrap(),
-                ),
-            )
-        }
+        Self::consume_action_approvals(
+            env.clone(),
+            set_admin_action_hash(&env, caller.clone(), admin.clone(), is_enabled),
+        );
 
-        vesting_ids
+        Self::apply_admin_change(&env, caller, admin, is_enabled);
     }
 
-    /// Allows a recipient to claim their vested tokens.
-    pub fn claim(env: Env, caller: Address, vesting_id: u64) {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
-
-        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id.clone());
-
+    /// Sets the delay in seconds between proposing and executing an admin change. Decreases apply
+    /// after the current delay.
+    pub fn set_admin_change_delay(env: Env, caller: Address, delay_secs: u64) {
         // Access control check
-        caller.require_auth();
-        if vesting.recipient != caller {
-            panic!("Not vesting owner");
-        }
+        Self::require_admin(&env, &caller);
 
-        assert!(
-            vesting.timelock <= env.ledger().timestamp(),
-            "Timelock enabled"
+        Self::set_delay(
+            &env,
+            ADMIN_CHANGE_DELAY,
+            PENDING_ADMIN_CHANGE_DELAY,
+            delay_secs,
         );
+    }
 
-        let vest_amount =
-            Self::calculate_vested_amount(env.clone(), vesting.clone(), env.ledger().timestamp());
-        let claimable = vest_amount - vesting.claimed_amount;
-
-        assert!(claimable != 0, "Insufficient balance to claim");
+    /// Returns the pending decrease of the admin change delay and when it can be applied, if any.
+    pub fn get_pending_admin_change_delay(env: Env) -> Option<(u64, u64)> {
+        Self::extend_instance_ttl(&env);
 
-        vesting.claimed_amount = vesting.claimed_amount + claimable;
+        env.storage().instance().get(&PENDING_ADMIN_CHANGE_DELAY)
+    }
 
-        let mut vesting_by_id: Map<u64, Vesting> = env
-            .storage()
-            .persistent()
-            .get(&VESTING_BY_ID)
-            .unwrap_or_else(|| Map::new(&env));
+    /// Returns the number of seconds between proposing and executing an admin change.
+    pub fn get_admin_change_delay(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
 
-        vesting_by_id.set(vesting_id, vesting.clone());
         env.storage()
-            .persistent()
-            .set(&VESTING_BY_ID, &vesting_by_id);
-
-        let reserved_tokens: i128 = env
-            .storage()
             .instance()
-            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .get(&ADMIN_CHANGE_DELAY)
             .unwrap_or(0)
-            - claimable;
+    }
+
+    /// Proposes to add or remove an admin, executable once the admin change delay has elapsed.
+    pub fn propose_admin_change(env: Env, caller: Address, admin: Address, is_enabled: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(
+            Self::is_admin(env.clone(), admin.clone()) != is_enabled,
+            "Flag provided already set"
+        );
+        assert!(
+            !env.storage().instance().has(&PENDING_ADMIN_CHANGE),
+            "Admin change already pending"
+        );
+
+        Self::consume_action_approvals(
+            env.clone(),
+            set_admin_action_hash(&env, caller.clone(), admin.clone(), is_enabled),
+        );
 
+        let ready_timestamp = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_admin_change_delay(env.clone()));
+
+        let pending_admin_change = PendingAdminChange {
+            proposer: caller.clone(),
+            admin: admin.clone(),
+            is_enabled,
+            ready_timestamp,
+        };
         env.storage()
             .instance()
-            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+            .set(&PENDING_ADMIN_CHANGE, &pending_admin_change);
 
         env.events().publish(
-            (CLAIMED,),
-            (vesting_id.clone(), caller.clone(), claimable.clone()),
+            (ADMIN_CHANGE_PROPOSED,),
+            (caller, admin, is_enabled, ready_timestamp),
         );
+    }
 
-        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+    /// Executes the pending admin change once its delay has elapsed.
+    pub fn execute_admin_change(env: Env, caller: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
 
-        TokenClient::new(&env, &token_address).transfer(
-            &env.current_contract_address(),
-            &caller,
-            &claimable,
+        let pending_admin_change = Self::get_pending_admin_change(env.clone());
+        assert!(
+            env.ledger().timestamp() >= pending_admin_change.ready_timestamp,
+            "Admin change delay not elapsed"
         );
-    }
 
-    /// Revokes a vesting arrangement before it has been fully claimed.
-    pub fn revoke_vesting(env: Env, caller: Address, vesting_id: u64) {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
-        Self::extend_persistent_ttl(&env, ADMINS);
+        env.storage().instance().remove(&PENDING_ADMIN_CHANGE);
 
-        let admins: Map<Address, bool> = env
-            .storage()
-            .persistent()
-            .get(&ADMINS)
-            .unwrap_or_else(|| Map::new(&env));
+        Self::apply_admin_change(
+            &env,
+            pending_admin_change.proposer,
+            pending_admin_change.admin,
+            pending_admin_change.is_enabled,
+        );
+    }
 
+    /// Cancels the pending admin change.
+    pub fn cancel_admin_change(env: Env, caller: Address) {
         // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
+        Self::require_admin(&env, &caller);
 
-        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
-        assert!(vesting.deactivation_timestamp == 0, "Vesting not active");
+        let pending_admin_change = Self::get_pending_admin_change(env.clone());
 
-        let final_vest_amount =
-            Self::calculate_vested_amount(env.clone(), vesting.clone(), vesting.end_timestamp);
-        assert!(
-            final_vest_amount != vesting.claimed_amount,
-            "All vested amount already claimed"
+        env.storage().instance().remove(&PENDING_ADMIN_CHANGE);
+
+        env.events().publish(
+            (ADMIN_CHANGE_CANCELLED,),
+            (
+                caller,
+                pending_admin_change.admin,
+                pending_admin_change.is_enabled,
+            ),
         );
+    }
 
-        vesting.deactivation_timestamp = env.ledger().timestamp();
+    /// Returns the pending admin change.
+    pub fn get_pending_admin_change(env: Env) -> PendingAdminChange {
+        Self::extend_instance_ttl(&env);
 
-        let mut vesting_by_id: Map<u64, Vesting> = env
-            .storage()
-            .persistent()
-            .get(&VESTING_BY_ID)
-            .unwrap_or_else(|| Map::new(&env));
-
-        vesting_by_id.set(vesting_id.clone(), vesting.clone());
         env.storage()
-            .persistent()
-            .set(&VESTING_BY_ID, &vesting_by_id);
-
-        let vested_amount_now =
-            Self::calculate_vested_amount(env.clone(), vesting.clone(), env.ledger().timestamp());
-        let amount_remaining = final_vest_amount - vested_amount_now;
-
-        let reserved_tokens = env
-            .storage()
             .instance()
-            .get(&TOKENS_RESERVED_FOR_VESTING)
-            .unwrap_or(0)
-            - amount_remaining;
+            .get(&PENDING_ADMIN_CHANGE)
+            .unwrap_or_else(|| panic!("No pending admin change"))
+    }
 
-        env.storage()
-            .instance()
-            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+    /// Rotates or clears the recovery address set at initialization, along with the admin
+    /// inactivity delay after which it can call `claim_recovery`.
+    pub fn set_recovery(env: Env, caller: Address, recovery: Option<Address>, delay_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
 
-        env.events().publish(
-            (VESTING_REVOKED,),
-            (
-                vesting_id.clone(),
-                vesting.clone().recipient,
-                amount_remaining,
-                vesting,
-            ),
+        Self::consume_action_approvals(
+            env.clone(),
+            recovery_action_hash(&env, caller.clone(), recovery.clone(), delay_secs),
         );
+
+        Self::store_recovery(&env, recovery, delay_secs);
     }
 
-    /// Calculates the vested amount for a given Vesting, at a given timestamp.
-    pub fn calculate_vested_amount(_env: Env, vesting: Vesting, reference_timestamp: u64) -> i128 {
-        Self::extend_instance_ttl(&_env);
+    /// Stores or clears the recovery address and its delay.
+    fn store_recovery(env: &Env, recovery: Option<Address>, delay_secs: u64) {
+        match recovery.clone() {
+            Some(recovery) => {
+                assert!(
+                    delay_secs >= MIN_RECOVERY_DELAY_SECS,
+                    "Invalid recovery delay"
+                );
+                env.storage().instance().set(&RECOVERY_ADDRESS, &recovery);
+                env.storage().instance().set(&RECOVERY_DELAY, &delay_secs);
+            }
+            None => {
+                env.storage().instance().remove(&RECOVERY_ADDRESS);
+                env.storage().instance().remove(&RECOVERY_DELAY);
+            }
+        }
 
-        let mut adjusted_reference_timestamp = reference_timestamp;
+        env.events()
+            .publish((RECOVERY_SET,), (recovery, delay_secs));
+    }
 
-        if vesting.deactivation_timestamp != 0
-            && adjusted_reference_timestamp > vesting.deactivation_timestamp
-        {
-            adjusted_reference_timestamp = vesting.deactivation_timestamp;
-        }
+    /// Returns the recovery address, if any.
+    pub fn get_recovery_address(env: Env) -> Option<Address> {
+        Self::extend_instance_ttl(&env);
 
-        let mut vesting_amount: i128 = 0;
+        env.storage().instance().get(&RECOVERY_ADDRESS)
+    }
 
-        if adjusted_reference_timestamp >= vesting.end_timestamp {
-            adjusted_reference_timestamp = vesting.end_timestamp;
-        }
+    /// Returns the number of seconds of admin inactivity required before recovery.
+    pub fn get_recovery_delay(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
 
-        if adjusted_reference_timestamp >= vesting.cliff_release_timestamp {
-            vesting_amount += vesting.cliff_amount;
-        }
+        env.storage().instance().get(&RECOVERY_DELAY).unwrap_or(0)
+    }
 
-        if vesting.initial_unlock > 0 && reference_timestamp >= vesting.start_timestamp {
-            vesting_amount += vesting.initial_unlock;
-        }
+    /// Returns the timestamp of the most recent admin action.
+    pub fn get_last_admin_action(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
 
-        let start_timestamp: u64;
+        env.storage()
+            .instance()
+            .get(&LAST_ADMIN_ACTION)
+            .unwrap_or(0)
+    }
 
-        if vesting.cliff_release_timestamp != 0 {
-            start_timestamp = vesting.cliff_release_timestamp;
-        } else {
-            start_timestamp = vesting.start_timestamp;
-        }
+    /// Lets the recovery address replace the admin set once no admin has acted for the recovery
+    /// delay.
+    pub fn claim_recovery(env: Env, recovery_caller: Address, new_admins: Vec<Address>) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, ADMINS);
 
-        if adjusted_reference_timestamp > start_timestamp {
-            let current_vesting_duration_secs = adjusted_reference_timestamp - start_timestamp;
-            let truncated_current_vesting_duration_secs = (current_vesting_duration_secs
-                / vesting.release_interval_secs)
-                * vesting.release_interval_secs;
+        recovery_caller.require_auth();
 
-            let final_vesting_duration_secs: i128 =
-                (vesting.end_timestamp - start_timestamp).into();
+        let recovery =
+            Self::get_recovery_address(env.clone()).unwrap_or_else(|| panic!("Recovery not set"));
+        assert!(recovery_caller == recovery, "Not the recovery address");
 
-            let truncated_current_vesting_duration_secs: i128 =
-                truncated_current_vesting_duration_secs.into();
+        let inactive_secs = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(Self::get_last_admin_action(env.clone()));
+        assert!(
+            inactive_secs > Self::get_recovery_delay(env.clone()),
+            "Admins still active"
+        );
 
-            let mut linear_vest_amount: i128;
+        Self::apply_admin_set(&env, recovery.clone(), new_admins);
+        env.storage().instance().remove(&PENDING_ADMIN_CHANGE);
+        env.storage().instance().remove(&PENDING_UPGRADE);
+        env.storage()
+            .instance()
+            .set(&LAST_ADMIN_ACTION, &env.ledger().timestamp());
 
-            if final_vesting_duration_secs == truncated_current_vesting_duration_secs {
-                linear_vest_amount = vesting.linear_vest_amount;
-            } else {
-                let number_of_intervals: i128 =
-                    final_vesting_duration_secs / vesting.release_interval_secs as i128;
-                let tokens_per_interval: i128 = vesting.linear_vest_amount / number_of_intervals;
-                let current_intervals: i128 =
-                    truncated_current_vesting_duration_secs / vesting.release_interval_secs as i128;
+        env.events().publish((RECOVERED,), recovery);
+    }
 
-                linear_vest_amount = tokens_per_interval * current_intervals;
-                let remainder = vesting.linear_vest_amount % number_of_intervals;
-                let remainder_distribution = (remainder * current_intervals) / number_of_intervals;
+    /// Atomically replaces the whole admin set of the Token Vesting Manager contract.
+    pub fn replace_admins(env: Env, caller: Address, new_admins: Vec<Address>) {
+        // Access control check
+        Self::require_admin(&env, &caller);
 
-                linear_vest_amount += remainder_distribution;
-            }
+        assert!(
+            Self::get_admin_change_delay(env.clone()) == 0,
+            "Admin change delay active"
+        );
+        Self::consume_action_approvals(
+            env.clone(),
+            replace_admins_action_hash(&env, caller.clone(), new_admins.clone()),
+        );
 
-            vesting_amount += linear_vest_amount;
-        }
+        Self::apply_admin_set(&env, caller, new_admins);
+    }
 
-        vesting_amount
+    /// Returns the number of admins for the Token Vesting Manager contract.
+    pub fn get_admins_count(env: Env) -> u32 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&ADMIN_COUNT).unwrap_or(0)
     }
 
-    /// Allows the admin to withdraw ERC20 tokens not locked in vesting.
-    pub fn withdraw_admin(env: Env, caller: Address, amount_requested: i128) {
+    /// Returns true if the given address is an admin, false otherwise.
+    pub fn is_admin(env: Env, address: Address) -> bool {
         Self::extend_instance_ttl(&env);
         Self::extend_persistent_ttl(&env, ADMINS);
 
@@ -487,365 +649,3056 @@ impl TokenVestingManager {
             .get(&ADMINS)
             .unwrap_or_else(|| Map::new(&env));
 
-        // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
-
-        let amount_remaining = Self::amount_to_withdraw_by_admin(env.clone());
-        assert!(amount_remaining >= amount_requested, "Insufficient balance");
-
-        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
-
-        TokenClient::new(&env, &token_address).transfer(
-            &env.current_contract_address(),
-            &caller,
-            &amount_requested,
-        );
-
-        env.events()
-            .publish((ADMIN_WITHDRAWN,), (caller, amount_requested));
+        admins.get(address).unwrap_or(false)
     }
 
-    /// Withdraws other ERC20 tokens accidentally sent to the contract's address.
-    pub fn withdraw_other_token(env: Env, caller: Address, other_token_address: Address) {
+    /// Returns the most recent admin changes, oldest first.
+    pub fn get_admin_change_history(env: Env) -> Vec<(u64, Address, Address, bool)> {
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, ADMINS);
 
-        let admins: Map<Address, bool> = env
-            .storage()
+        env.storage()
             .persistent()
-            .get(&ADMINS)
-            .unwrap_or_else(|| Map::new(&env));
+            .get(&ADMIN_HISTORY)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
 
+    /// Sets the number of admin approvals required for sensitive admin actions, and how long an
+    /// approval remains valid.
+    pub fn set_action_threshold(env: Env, caller: Address, threshold: u32, window_secs: u64) {
         // Access control check
-        Self::admin_check(caller.clone(), admins.clone());
+        Self::require_admin(&env, &caller);
 
+        let admin_count: u32 = env.storage().instance().get(&ADMIN_COUNT).unwrap_or(0);
         assert!(
-            other_token_address != Self::get_token_address(env.clone()),
-            "Invalid other token"
+            threshold != 0 && threshold <= admin_count,
+            "Invalid threshold"
+        );
+        assert!(
+            threshold == 1 || window_secs != 0,
+            "Invalid approval window"
         );
 
-        let balance =
-            TokenClient::new(&env, &other_token_address).balance(&env.current_contract_address());
-
-        TokenClient::new(&env, &other_token_address).transfer(
-            &env.current_contract_address(),
-            &caller,
-            &balance,
+        Self::consume_action_approvals(
+            env.clone(),
+            threshold_action_hash(&env, caller.clone(), threshold, window_secs),
         );
 
+        env.storage().instance().set(&ACTION_THRESHOLD, &threshold);
+        env.storage().instance().set(&APPROVAL_WINDOW, &window_secs);
+
         env.events()
-            .publish((ADMIN_WITHDRAWN_OTHER,), (caller, balance));
+            .publish((THRESHOLD_SET,), (threshold, window_secs));
     }
 
-    /// Returns the amount of tokens that are available for the admin to withdraw.
-    pub fn amount_to_withdraw_by_admin(env: Env) -> i128 {
+    /// Returns the number of distinct admin approvals required for sensitive admin actions.
+    pub fn get_action_threshold(env: Env) -> u32 {
         Self::extend_instance_ttl(&env);
 
-        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
-
-        let balance =
-            TokenClient::new(&env, &token_address).balance(&env.current_contract_address());
+        env.storage().instance().get(&ACTION_THRESHOLD).unwrap_or(1)
+    }
 
-        let reserved_tokens: i128 = env
-            .storage()
-            .instance()
-            .get(&TOKENS_RESERVED_FOR_VESTING)
-            .unwrap_or(0);
+    /// Returns the number of seconds an admin approval remains valid.
+    pub fn get_approval_window(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
 
-        balance - reserved_tokens
+        env.storage().instance().get(&APPROVAL_WINDOW).unwrap_or(0)
     }
 
-    /// Retrieves information about a specific vesting arrangement.
-    pub fn get_vesting_info(env: Env, vesting_id: u64) -> Vesting {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+    /// Records the caller's approval of the action identified by `action_hash`.
+    pub fn propose_action(env: Env, caller: Address, action_hash: BytesN<32>) {
+        // Access control check
+        Self::require_admin(&env, &caller);
 
-        let vesting_by_id: Map<u64, Vesting> = env
+        let mut action_approvals: Map<BytesN<32>, Map<Address, u64>> = env
             .storage()
             .persistent()
-            .get(&VESTING_BY_ID)
+            .get(&ACTION_APPROVALS)
             .unwrap_or_else(|| Map::new(&env));
 
-        // This will panic if there is no vesting associated with a given id.
-        vesting_by_id.get(vesting_id).unwrap()
-    }
-
-    /// Returns all recipient addresses which have at least one vesting schedule set.
-    pub fn get_all_recipients(env: Env) -> Vec<Address> {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENTS);
+        let mut approvals: Map<Address, u64> = action_approvals
+            .get(action_hash.clone())
+            .unwrap_or_else(|| Map::new(&env));
+        approvals.set(caller.clone(), env.ledger().timestamp());
+        action_approvals.set(action_hash.clone(), approvals);
 
         env.storage()
             .persistent()
-            .get(&RECIPIENTS)
-            .unwrap_or_else(|| Vec::new(&env))
+            .set(&ACTION_APPROVALS, &action_approvals);
+        Self::extend_persistent_ttl(&env, ACTION_APPROVALS);
+
+        env.events()
+            .publish((ACTION_APPROVED,), (action_hash, caller));
     }
 
-    /// Returns the list of recipients in a specific range, `from` being inclusive and `to` being exclusive.
-    pub fn get_all_recipients_sliced(env: Env, from: u32, to: u32) -> Vec<Address> {
+    /// Returns the number of valid admin approvals recorded for the action identified by `action_hash`.
+    pub fn get_action_approvals(env: Env, action_hash: BytesN<32>) -> u32 {
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENTS);
+        Self::extend_persistent_ttl(&env, ADMINS);
 
-        let recipients: Vec<Address> = env
+        let admins: Map<Address, bool> = env
             .storage()
             .persistent()
-            .get(&RECIPIENTS)
-            .unwrap_or_else(|| Vec::new(&env));
-
-        recipients.slice(from..to)
-    }
-
-    /// Returns the number of recipients.
-    pub fn get_all_recipients_len(env: Env) -> u32 {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENTS);
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(&env));
 
-        let recipients: Vec<Address> = env
+        let action_approvals: Map<BytesN<32>, Map<Address, u64>> = env
             .storage()
             .persistent()
-            .get(&RECIPIENTS)
-            .unwrap_or_else(|| Vec::new(&env));
+            .get(&ACTION_APPROVALS)
+            .unwrap_or_else(|| Map::new(&env));
 
-        recipients.len()
-    }
+        let approvals: Map<Address, u64> = action_approvals
+            .get(action_hash)
+            .unwrap_or_else(|| Map::new(&env));
 
-    /// Returns the list of vestings for the recipient.
-    pub fn get_all_recipient_vestings(env: Env, recipient: Address) -> Vec<u64> {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+        let window: u64 = Self::get_approval_window(env.clone());
+        let now = env.ledger().timestamp();
 
-        let recipient_vestings: Map<Address, Vec<u64>> = env
-            .storage()
-            .persistent()
-            .get(&RECIPIENT_VESTINGS)
-            .unwrap_or_else(|| Map::new(&env));
+        // Only approvals from current admins, given within the approval window, are counted.
+        let mut count: u32 = 0;
+        for (admin, approved_at) in approvals.iter() {
+            if admins.get(admin).unwrap_or(false) && approved_at.saturating_add(window) >= now {
+                count += 1;
+            }
+        }
 
-        recipient_vestings
-            .get(recipient)
-            .unwrap_or_else(|| Vec::new(&env))
+        count
     }
 
-    /// Returns the list of vestings for the recipient in a specific range, `from` being inclusive and
-    /// `to` being exclusive.
-    pub fn get_all_recipient_vesting_sliced(
+    /// Creates a vesting schedule for a recipient and returns a vesting ID.
+    pub fn create_vesting(
         env: Env,
-        from: u32,
-        to: u32,
+        caller: Address,
         recipient: Address,
-    ) -> Vec<u64> {
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> u64 {
         Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
-
-        let recipient_vestings: Map<Address, Vec<u64>> = env
-            .storage()
-            .persistent()
-            .get(&RECIPIENT_VESTINGS)
-            .unwrap_or_else(|| Map::new(&env));
-
-        let vestings: Vec<u64> = recipient_vestings
-            .get(recipient)
-            .unwrap_or_else(|| Vec::new(&env));
+        Self::extend_persistent_ttl(&env, ADMINS);
 
-        vestings.slice(from..to)
-    }
+        let admins: Map<Address, bool> = env.storage().persistent().get(&ADMINS).unwrap();
 
-    /// Returns the length of all vestings for the recipient.
-    pub fn get_all_recipient_vestings_len(env: Env, recipient: Address) -> u32 {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+        // Access control check
+        Self::admin_check(caller.clone(), admins.clone());
 
-        let recipient_vestings: Map<Address, Vec<u64>> = env
-            .storage()
-            .persistent()
-            .get(&RECIPIENT_VESTINGS)
-            .unwrap_or_else(|| Map::new(&env));
+        Self::create_vesting_internal(
+            env.clone(),
+            caller.clone(),
+            recipient.clone(),
+            start_timestamp,
+            end_timestamp,
+            timelock,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+        )
+    }
+
+    /// Sets the release interval used by `create_vesting_default_interval` of the operator.
+    pub fn set_default_release_interval(env: Env, caller: Address, release_interval_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, DEFAULT_INTERVAL, release_interval_secs);
+    }
+
+    /// Returns the release interval used by `create_vesting_default_interval`, 0 if not set.
+    pub fn get_default_release_interval(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&DEFAULT_INTERVAL).unwrap_or(0)
+    }
+
+    /// Creates a vesting schedule and immediately transfers its initial unlock to the recipient if
+    /// already claimable.
+    pub fn create_and_push_initial(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> u64 {
+        let vesting_id = Self::create_vesting(
+            env.clone(),
+            caller,
+            recipient,
+            start_timestamp,
+            end_timestamp,
+            timelock,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+        );
+
+        let now = env.ledger().timestamp();
+        if initial_unlock > 0
+            && start_timestamp <= now
+            && timelock <= now
+            && !Self::is_ack_required(env.clone())
+        {
+            let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+            Self::pay_out(env, vesting_id, vesting, initial_unlock);
+        }
+
+        vesting_id
+    }
+
+    /// Creates a vesting schedule which can never be revoked, and returns its vesting ID.
+    pub fn create_irrevocable_vesting(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> u64 {
+        let vesting_id = Self::create_vesting(
+            env.clone(),
+            caller,
+            recipient,
+            start_timestamp,
+            end_timestamp,
+            timelock,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+        );
+
+        Self::mark_irrevocable(&env, vesting_id);
+
+        vesting_id
+    }
+
+    /// Returns true if the given vesting cannot be revoked, false otherwise.
+    pub fn is_irrevocable(env: Env, vesting_id: u64) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        Self::vesting_flag(&env, IRREVOCABLE_VESTINGS, vesting_id)
+    }
+
+    /// Creates vesting schedules in batch for multiple recipients, reverting all of them if any is
+    /// invalid.
+    pub fn create_vesting_batch(
+        env: Env,
+        caller: Address,
+        create_vesting_batch_params: CreateVestingBatchParams,
+    ) -> Vec<u64> {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let length: u32 = create_vesting_batch_params.recipients.len();
+        assert!(
+            create_vesting_batch_params.start_timestamps.len() == length
+                && create_vesting_batch_params.end_timestamps.len() == length
+                && create_vesting_batch_params.timelocks.len() == length
+                && create_vesting_batch_params.initial_unlocks.len() == length
+                && create_vesting_batch_params.cliff_release_timestamps.len() == length
+                && create_vesting_batch_params.cliff_amounts.len() == length
+                && create_vesting_batch_params.release_interval_secs.len() == length
+                && create_vesting_batch_params.linear_vest_amounts.len() == length,
+            "Array length mismatch"
+        );
+
+        // Validate every row before creating any vesting, so an invalid row rejects the whole
+        // batch without storing anything or pulling any funds.
+        let mut batch_amount: i128 = 0;
+        for i in 0..length {
+            let amount = Self::validate_vesting_params(
+                env.clone(),
+                create_vesting_batch_params.recipients.get(i).unwrap(),
+                create_vesting_batch_params.start_timestamps.get(i).unwrap(),
+                create_vesting_batch_params.end_timestamps.get(i).unwrap(),
+                create_vesting_batch_params.initial_unlocks.get(i).unwrap(),
+                create_vesting_batch_params
+                    .cliff_release_timestamps
+                    .get(i)
+                    .unwrap(),
+                create_vesting_batch_params.cliff_amounts.get(i).unwrap(),
+                create_vesting_batch_params
+                    .release_interval_secs
+                    .get(i)
+                    .unwrap(),
+                create_vesting_batch_params
+                    .linear_vest_amounts
+                    .get(i)
+                    .unwrap(),
+            );
+            batch_amount = batch_amount
+                .checked_add(amount)
+                .unwrap_or_else(|| panic!("Amount overflow"));
+        }
+
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+        assert!(
+            TokenClient::new(&env, &token_address)
+                .allowance(&caller, &env.current_contract_address())
+                >= batch_amount,
+            "Insufficient allowance"
+        );
+
+        let mut vesting_ids: Vec<u64> = Vec::new(&env);
+
+        for i in 0..length {
+            vesting_ids.insert(
+                i,
+                Self::create_vesting_internal(
+                    env.clone(),
+                    caller.clone(),
+                    create_vesting_batch_params.recipients.get(i).unwrap(),
+                    create_vesting_batch_params.start_timestamps.get(i).unwrap(),
+                    create_vesting_batch_params.end_timestamps.get(i).unwrap(),
+                    create_vesting_batch_params.timelocks.get(i).unwrap(),
+                    create_vesting_batch_params.initial_unlocks.get(i).unwrap(),
+                    create_vesting_batch_params
+                        .cliff_release_timestamps
+                        .get(i)
+                        .unwrap(),
+                    create_vesting_batch_params.cliff_amounts.get(i).unwrap(),
+                    create_vesting_batch_params
+                        .release_interval_secs
+                        .get(i)
+                        .unwrap(),
+                    create_vesting_batch_params
+                        .linear_vest_amounts
+                        .get(i)
+                        .unwrap(),
+                ),
+            )
+        }
+
+        vesting_ids
+    }
+
+    /// Allows a recipient to claim their vested tokens.
+    pub fn claim(env: Env, caller: Address, vesting_id: u64) {
+        let (claimable, reserved_tokens) =
+            Self::settle_claim(env.clone(), caller.clone(), vesting_id);
+        Self::add_recipient_claimed(&env, caller.clone(), claimable);
+
+        env.events().publish(
+            (CLAIMED, caller.clone(), vesting_id),
+            (vesting_id, caller.clone(), claimable, reserved_tokens),
+        );
+
+        Self::transfer_claimed(
+            &env,
+            Self::payout_destination(env.clone(), caller.clone()),
+            claimable,
+        );
+
+        Self::call_claim_hook(&env, caller, claimable);
+    }
+
+    /// Claims the vested tokens of a vesting into a new irrevocable vesting for the same recipient,
+    /// vesting every second over `new_duration_secs` and subject to every vesting creation rule.
+    /// No tokens are paid out, so the claimed total of the recipient is unchanged.
+    pub fn claim_compound(
+        env: Env,
+        caller: Address,
+        vesting_id: u64,
+        new_duration_secs: u64,
+    ) -> u64 {
+        let (claimable, _) = Self::settle_claim(env.clone(), caller.clone(), vesting_id);
+
+        let start_timestamp = env.ledger().timestamp();
+        let end_timestamp = start_timestamp
+            .checked_add(new_duration_secs)
+            .unwrap_or_else(|| panic!("Invalid vesting duration"));
+
+        let compounded_vesting_id = Self::record_vesting(
+            env.clone(),
+            caller.clone(),
+            caller,
+            start_timestamp,
+            end_timestamp,
+            0,
+            0,
+            0,
+            0,
+            1,
+            claimable,
+            0,
+        );
+        Self::mark_irrevocable(&env, compounded_vesting_id);
+
+        compounded_vesting_id
+    }
+
+    /// Allows a recipient to claim the vested tokens of all their claimable vestings at once,
+    /// returning the total amount claimed.
+    pub fn claim_all(env: Env, caller: Address) -> i128 {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+
+        // Access control check
+        caller.require_auth();
+
+        assert!(!Self::is_claims_paused(env.clone()), "Claims paused");
+
+        let vesting_ids: Vec<u64> = Self::get_all_recipient_vestings(env.clone(), caller.clone());
+
+        let mut vesting_by_id: Map<u64, Vesting> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_BY_ID)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut total_claimable: i128 = 0;
+        let mut reserved_tokens: i128 = env
+            .storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0);
+        let mut skipped: Vec<(u32, u64, u32)> = Vec::new(&env);
+
+        for i in 0..vesting_ids.len() {
+            let vesting_id: u64 = vesting_ids.get(i).unwrap();
+            let mut vesting: Vesting = vesting_by_id.get(vesting_id).unwrap();
+
+            let (skip_reason, claimable) = Self::claim_skip_reason(&env, vesting_id, &vesting);
+            if skip_reason != 0 {
+                skipped.push_back((i, vesting_id, skip_reason));
+                continue;
+            }
+            Self::record_claim_timestamp(env.clone(), vesting_id);
+
+            vesting.claimed_amount += claimable;
+            vesting_by_id.set(vesting_id, vesting);
+            total_claimable += claimable;
+            reserved_tokens -= claimable;
+
+            env.events().publish(
+                (CLAIMED, caller.clone(), vesting_id),
+                (vesting_id, caller.clone(), claimable, reserved_tokens),
+            );
+        }
+
+        if total_claimable == 0 {
+            return 0;
+        }
+
+        Self::add_recipient_claimed(&env, caller.clone(), total_claimable);
+
+        for skipped_entry in skipped.iter() {
+            env.events().publish((BATCH_SKIPPED,), skipped_entry);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&VESTING_BY_ID, &vesting_by_id);
+
+        env.storage()
+            .instance()
+            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+
+        Self::transfer_claimed(
+            &env,
+            Self::payout_destination(env.clone(), caller.clone()),
+            total_claimable,
+        );
+
+        Self::call_claim_hook(&env, caller, total_claimable);
+
+        total_claimable
+    }
+
+    /// Revokes a vesting arrangement before it has been fully claimed.
+    pub fn revoke_vesting(env: Env, caller: Address, vesting_id: u64) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, ADMINS);
+
+        let admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        // Access control check
+        Self::admin_check(caller.clone(), admins.clone());
+
+        assert!(
+            !Self::requires_revoke_approval(env.clone(), vesting_id),
+            "Revocation requires approval"
+        );
+
+        Self::revoke_vesting_internal(env.clone(), vesting_id);
+    }
+
+    /// Revokes a vesting arrangement and transfers its unvested tokens to the given destination.
+    pub fn revoke_vesting_refund_to(
+        env: Env,
+        caller: Address,
+        vesting_id: u64,
+        destination: Address,
+    ) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, ADMINS);
+
+        let admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        // Access control check
+        Self::admin_check(caller.clone(), admins.clone());
+
+        assert!(
+            !Self::requires_revoke_approval(env.clone(), vesting_id),
+            "Revocation requires approval"
+        );
+        assert!(
+            Self::get_withdraw_delay(env.clone()) == 0,
+            "Withdrawal delay enabled"
+        );
+        assert!(
+            Self::get_action_threshold(env.clone()) <= 1,
+            "Withdrawal requires approval"
+        );
+
+        let refund_amount = Self::revoke_vesting_internal(env.clone(), vesting_id);
+
+        if refund_amount == 0 {
+            return;
+        }
+
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+
+        TokenClient::new(&env, &token_address).transfer(
+            &env.current_contract_address(),
+            &destination,
+            &refund_amount,
+        );
+
+        env.events()
+            .publish((REVOKE_REFUNDED,), (vesting_id, destination, refund_amount));
+    }
+
+    /// Sets the unvested amount above which a revocation requires a second admin approval, 0
+    /// disabling it. Raises need a second admin.
+    pub fn set_revoke_approval_threshold(env: Env, caller: Address, threshold: i128) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(threshold >= 0, "Invalid threshold");
+
+        let current_threshold = Self::get_revoke_approval_threshold(env.clone());
+        if current_threshold != 0 && (threshold == 0 || threshold > current_threshold) {
+            match Self::get_pending_revoke_threshold(env.clone()) {
+                Some((pending_threshold, proposer))
+                    if pending_threshold == threshold
+                        && proposer != caller
+                        && Self::is_admin(env.clone(), proposer.clone()) => {}
+                _ => {
+                    env.storage()
+                        .instance()
+                        .set(&PENDING_REVOKE_THRESHOLD, &(threshold, caller.clone()));
+                    env.events()
+                        .publish((CONFIG_SET,), (PENDING_REVOKE_THRESHOLD, threshold));
+                    return;
+                }
+            }
+        }
+        env.storage().instance().remove(&PENDING_REVOKE_THRESHOLD);
+
+        Self::set_config(&env, REVOKE_APPROVAL_THRESHOLD, threshold);
+    }
+
+    /// Returns the unvested amount above which revoking a vesting requires a second admin approval.
+    pub fn get_revoke_approval_threshold(env: Env) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&REVOKE_APPROVAL_THRESHOLD)
+            .unwrap_or(0)
+    }
+
+    /// Returns the pending revoke approval threshold and the admin who proposed it, if any.
+    pub fn get_pending_revoke_threshold(env: Env) -> Option<(i128, Address)> {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&PENDING_REVOKE_THRESHOLD)
+    }
+
+    /// Proposes the revocation of a vesting, to be approved by a different admin.
+    pub fn propose_revocation(env: Env, caller: Address, vesting_id: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(vesting.deactivation_timestamp == 0, "Vesting not active");
+
+        let mut pending_revocations: Map<u64, Address> = env
+            .storage()
+            .persistent()
+            .get(&PENDING_REVOCATIONS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        assert!(
+            !pending_revocations.contains_key(vesting_id),
+            "Revocation already proposed"
+        );
+
+        pending_revocations.set(vesting_id, caller.clone());
+        env.storage()
+            .persistent()
+            .set(&PENDING_REVOCATIONS, &pending_revocations);
+        Self::extend_persistent_ttl(&env, PENDING_REVOCATIONS);
+
+        env.events()
+            .publish((REVOCATION_PROPOSED,), (vesting_id, caller));
+    }
+
+    /// Approves the pending revocation of a vesting proposed by another admin, and revokes it.
+    pub fn approve_revocation(env: Env, caller: Address, vesting_id: u64) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, ADMINS);
+
+        let admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        // Access control check
+        Self::admin_check(caller.clone(), admins.clone());
+
+        let mut pending_revocations: Map<u64, Address> = env
+            .storage()
+            .persistent()
+            .get(&PENDING_REVOCATIONS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let proposer = pending_revocations
+            .get(vesting_id)
+            .unwrap_or_else(|| panic!("No pending revocation"));
+        assert!(proposer != caller, "Proposer cannot approve");
+        assert!(
+            admins.get(proposer).unwrap_or(false),
+            "Proposer not an admin"
+        );
+
+        pending_revocations.remove(vesting_id);
+        env.storage()
+            .persistent()
+            .set(&PENDING_REVOCATIONS, &pending_revocations);
+
+        Self::revoke_vesting_internal(env.clone(), vesting_id);
+    }
+
+    /// Returns the admin who proposed the pending revocation of a vesting, if any.
+    pub fn get_pending_revocation(env: Env, vesting_id: u64) -> Option<Address> {
+        Self::extend_instance_ttl(&env);
+
+        let pending_revocations: Map<u64, Address> = env
+            .storage()
+            .persistent()
+            .get(&PENDING_REVOCATIONS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        pending_revocations.get(vesting_id)
+    }
+
+    /// Revokes a vesting arrangement and transfers the vested but unclaimed tokens to the
+    /// recipient. When the recipient could not claim them now, e.g. during the timelock or a
+    /// pause, they are left claimable instead.
+    pub fn revoke_and_settle(env: Env, caller: Address, vesting_id: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        assert!(
+            !Self::requires_revoke_approval(env.clone(), vesting_id),
+            "Revocation requires approval"
+        );
+
+        Self::revoke_vesting_internal(env.clone(), vesting_id);
+
+        if Self::is_claims_paused(env.clone()) {
+            return;
+        }
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        let (skip_reason, settled_amount) = Self::claim_skip_reason(&env, vesting_id, &vesting);
+        if skip_reason != 0 {
+            return;
+        }
+        Self::record_claim_timestamp(env.clone(), vesting_id);
+
+        Self::pay_out(env, vesting_id, vesting, settled_amount);
+    }
+
+    /// Moves every vesting of the caller to `new_recipient`, e.g. to migrate to a new wallet,
+    /// along with the claimed total of the moved vestings.
+    pub fn transfer_all_vestings(env: Env, caller: Address, new_recipient: Address) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+
+        caller.require_auth();
+
+        assert!(caller != new_recipient, "Same recipient");
+        assert!(
+            Self::is_recipient_allowed(env.clone(), new_recipient.clone()),
+            "Recipient not allowed"
+        );
+        assert!(
+            !Self::is_admin_recipients_blocked(env.clone())
+                || !Self::is_admin(env.clone(), new_recipient.clone()),
+            "Recipient is an admin"
+        );
+        assert!(
+            Self::get_mandatory_destination(env.clone(), caller.clone()).is_none(),
+            "Mandatory destination set"
+        );
+
+        let mut recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let moved_ids: Vec<u64> = recipient_vestings
+            .get(caller.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        assert!(!moved_ids.is_empty(), "No vestings to transfer");
+
+        let mut new_recipient_ids: Vec<u64> = recipient_vestings
+            .get(new_recipient.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+
+        assert!(
+            new_recipient_ids.len() + moved_ids.len() <= Self::recipient_vestings_limit(&env),
+            "Too many vestings for recipient"
+        );
+
+        let mut recipients: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENTS)
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = recipients.first_index_of(caller.clone()) {
+            recipients.remove(index);
+        }
+        if !recipients.contains(new_recipient.clone()) {
+            recipients.push_back(new_recipient.clone());
+        }
+        env.storage().persistent().set(&RECIPIENTS, &recipients);
+
+        let mut vesting_by_id: Map<u64, Vesting> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_BY_ID)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut moved_claimed: i128 = 0;
+        for vesting_id in moved_ids.iter() {
+            let mut vesting = vesting_by_id
+                .get(vesting_id)
+                .unwrap_or_else(|| panic!("Vesting not found"));
+            moved_claimed += vesting.claimed_amount;
+            vesting.recipient = new_recipient.clone();
+            vesting_by_id.set(vesting_id, vesting);
+            new_recipient_ids.push_back(vesting_id);
+
+            env.events().publish(
+                (VESTING_TRANSFERRED, new_recipient.clone(), vesting_id),
+                (vesting_id, caller.clone(), new_recipient.clone()),
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&VESTING_BY_ID, &vesting_by_id);
+
+        recipient_vestings.remove(caller.clone());
+        recipient_vestings.set(new_recipient.clone(), new_recipient_ids);
+        env.storage()
+            .persistent()
+            .set(&RECIPIENT_VESTINGS, &recipient_vestings);
+
+        // Reconciliations can record more as claimed than was paid out, so at most the claimed
+        // total of the caller moves along.
+        let moved_claimed = moved_claimed.min(Self::get_recipient_claimed_total(
+            env.clone(),
+            caller.clone(),
+        ));
+        if moved_claimed != 0 {
+            Self::add_recipient_claimed(&env, caller.clone(), -moved_claimed);
+            Self::add_recipient_claimed(&env, new_recipient.clone(), moved_claimed);
+        }
+
+        if let Some(hook) = Self::get_claim_hook(env.clone(), caller.clone()) {
+            let claim_hook_entry = (CLAIM_HOOKS, new_recipient.clone());
+            if !env.storage().persistent().has(&claim_hook_entry) {
+                env.storage().persistent().set(&claim_hook_entry, &hook);
+                env.storage().persistent().extend_ttl(
+                    &claim_hook_entry,
+                    LIFETIME_THRESHOLD,
+                    EXTENSION_AMOUNT,
+                );
+                env.events()
+                    .publish((CLAIM_HOOK_SET,), (new_recipient, Some(hook)));
+            }
+            env.storage().persistent().remove(&(CLAIM_HOOKS, caller));
+        }
+    }
+
+    /// Moves `split_bps` basis points of an active vesting to a new vesting for `new_recipient`
+    /// keeping its funder, flags and cliff ramp, returning its vesting ID. Moving an unvested
+    /// amount above the revoke approval threshold is rejected.
+    pub fn split_vesting(
+        env: Env,
+        caller: Address,
+        vesting_id: u64,
+        new_recipient: Address,
+        split_bps: u32,
+    ) -> u64 {
+        // Access control check
+        Self::require_admin(&env, &caller);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        assert!(split_bps > 0 && split_bps < 10000, "Invalid split");
+
+        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(vesting.deactivation_timestamp == 0, "Vesting not active");
+        assert!(
+            !Self::is_irrevocable(env.clone(), vesting_id),
+            "Vesting irrevocable"
+        );
+
+        let split = |amount: i128| -> i128 {
+            amount
+                .checked_mul(split_bps as i128)
+                .unwrap_or_else(|| panic!("Amount overflow"))
+                / 10000
+        };
+
+        let mut new_vesting = Vesting {
+            recipient: new_recipient.clone(),
+            start_timestamp: vesting.start_timestamp,
+            end_timestamp: vesting.end_timestamp,
+            deactivation_timestamp: 0,
+            timelock: vesting.timelock,
+            release_interval_secs: vesting.release_interval_secs,
+            cliff_release_timestamp: vesting.cliff_release_timestamp,
+            initial_unlock: split(vesting.initial_unlock),
+            cliff_amount: split(vesting.cliff_amount),
+            linear_vest_amount: split(vesting.linear_vest_amount),
+            claimed_amount: 0,
+        };
+
+        vesting.initial_unlock -= new_vesting.initial_unlock;
+        vesting.cliff_amount -= new_vesting.cliff_amount;
+        vesting.linear_vest_amount -= new_vesting.linear_vest_amount;
+        // The original part must keep a valid schedule: a cliff amount if it has a cliff,
+        // and something to vest otherwise.
+        assert!(
+            vesting.cliff_amount != 0
+                || (vesting.cliff_release_timestamp == 0 && vesting.linear_vest_amount != 0),
+            "Invalid split"
+        );
+
+        // Rounding may leave either part with slightly less vested than its proportional
+        // claimed amount, so the new part's claimed amount is capped at what it has vested.
+        let now = env.ledger().timestamp();
+        let cliff_ramp_secs = Self::get_cliff_ramp(env.clone(), vesting_id);
+        new_vesting.claimed_amount =
+            split(vesting.claimed_amount).min(Self::calculate_ramped_vested_amount(
+                env.clone(),
+                new_vesting.clone(),
+                now,
+                cliff_ramp_secs,
+            ));
+        vesting.claimed_amount -= new_vesting.claimed_amount;
+        assert!(
+            vesting.claimed_amount
+                <= Self::calculate_ramped_vested_amount(
+                    env.clone(),
+                    vesting.clone(),
+                    now,
+                    cliff_ramp_secs
+                ),
+            "Invalid split"
+        );
+
+        let revoke_approval_threshold = Self::get_revoke_approval_threshold(env.clone());
+        assert!(
+            revoke_approval_threshold == 0
+                || new_vesting.initial_unlock
+                    + new_vesting.cliff_amount
+                    + new_vesting.linear_vest_amount
+                    - Self::calculate_ramped_vested_amount(
+                        env.clone(),
+                        new_vesting.clone(),
+                        now,
+                        cliff_ramp_secs,
+                    )
+                    <= revoke_approval_threshold,
+            "Split requires approval"
+        );
+
+        // Recording the new vesting reserves its whole amount, which is already reserved.
+        Self::release_reserved(
+            &env,
+            new_vesting.initial_unlock + new_vesting.cliff_amount + new_vesting.linear_vest_amount,
+        );
+
+        let new_vesting_id = Self::record_vesting(
+            env.clone(),
+            caller,
+            new_recipient.clone(),
+            new_vesting.start_timestamp,
+            new_vesting.end_timestamp,
+            new_vesting.timelock,
+            new_vesting.initial_unlock,
+            new_vesting.cliff_release_timestamp,
+            new_vesting.cliff_amount,
+            new_vesting.release_interval_secs,
+            new_vesting.linear_vest_amount,
+            new_vesting.claimed_amount,
+        );
+
+        if cliff_ramp_secs != 0 {
+            Self::record_cliff_ramp(&env, new_vesting_id, cliff_ramp_secs);
+        }
+        Self::copy_vesting_entry(&env, VESTING_FUNDERS, vesting_id, new_vesting_id);
+        Self::copy_vesting_entry(&env, PAUSED_VESTINGS, vesting_id, new_vesting_id);
+        Self::copy_vesting_entry(&env, ACKNOWLEDGED_VESTINGS, vesting_id, new_vesting_id);
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        env.events()
+            .publish((VESTING_SPLIT,), (vesting_id, new_vesting_id, split_bps));
+
+        new_vesting_id
+    }
+
+    /// Overrides the claimed amount of a vesting for reconciliation, without transferring tokens.
+    /// The new claimed amount cannot exceed the amount vested so far.
+    pub fn admin_set_claimed(env: Env, caller: Address, vesting_id: u64, new_claimed: i128) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(
+            !Self::is_irrevocable(env.clone(), vesting_id),
+            "Vesting irrevocable"
+        );
+
+        let vested_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            env.ledger().timestamp(),
+        );
+        assert!(
+            new_claimed >= 0 && new_claimed <= vested_amount,
+            "Invalid claimed amount"
+        );
+
+        let previous_claimed = vesting.claimed_amount;
+        assert!(
+            previous_claimed != new_claimed,
+            "Claimed amount already set"
+        );
+
+        // Raising the claimed amount takes tokens from the recipient like a revocation does.
+        let revoke_approval_threshold = Self::get_revoke_approval_threshold(env.clone());
+        assert!(
+            revoke_approval_threshold == 0
+                || new_claimed - previous_claimed <= revoke_approval_threshold,
+            "Claimed amount change requires approval"
+        );
+
+        vesting.claimed_amount = new_claimed;
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        let reserved_tokens = Self::release_reserved(&env, new_claimed - previous_claimed);
+        assert!(
+            new_claimed > previous_claimed || reserved_tokens <= Self::get_balance(env.clone()),
+            "Insufficient balance"
+        );
+
+        env.events().publish(
+            (CLAIMED_AMOUNT_SET,),
+            (vesting_id, caller, previous_claimed, new_claimed),
+        );
+    }
+
+    /// Deletes a vesting which has nothing left to claim, to reclaim its storage.
+    /// A recipient left without vestings is removed from the recipients.
+    pub fn purge_vesting(env: Env, caller: Address, vesting_id: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+
+        let final_vest_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.end_timestamp,
+        );
+        assert!(
+            final_vest_amount == vesting.claimed_amount,
+            "Vesting not fully claimed"
+        );
+
+        let mut vesting_by_id: Map<u64, Vesting> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_BY_ID)
+            .unwrap_or_else(|| Map::new(&env));
+
+        vesting_by_id.remove(vesting_id);
+        env.storage()
+            .persistent()
+            .set(&VESTING_BY_ID, &vesting_by_id);
+
+        let mut recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut recipient_ids: Vec<u64> = recipient_vestings
+            .get(vesting.recipient.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(index) = recipient_ids.first_index_of(vesting_id) {
+            recipient_ids.remove(index);
+        }
+
+        if recipient_ids.is_empty() {
+            recipient_vestings.remove(vesting.recipient.clone());
+
+            let mut recipients: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&RECIPIENTS)
+                .unwrap_or_else(|| Vec::new(&env));
+            if let Some(index) = recipients.first_index_of(vesting.recipient.clone()) {
+                recipients.remove(index);
+            }
+            env.storage().persistent().set(&RECIPIENTS, &recipients);
+        } else {
+            recipient_vestings.set(vesting.recipient.clone(), recipient_ids);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&RECIPIENT_VESTINGS, &recipient_vestings);
+
+        // Clean up the per-vesting side records.
+        for key in [
+            LAST_CLAIM_TIMESTAMPS,
+            PAUSED_VESTINGS,
+            CLIFF_RAMPS,
+            ACKNOWLEDGED_VESTINGS,
+            VESTING_FUNDERS,
+            IRREVOCABLE_VESTINGS,
+            PENDING_REVOCATIONS,
+        ] {
+            Self::remove_vesting_entry(&env, key, vesting_id);
+        }
+
+        env.events().publish(
+            (VESTING_PURGED, vesting.recipient.clone(), vesting_id),
+            (vesting_id, vesting.recipient),
+        );
+    }
+
+    /// Sets the number of seconds after the end of a vesting before it can be reclaimed, 0
+    /// disabling reclaiming.
+    pub fn set_reclaim_grace_secs(env: Env, caller: Address, grace_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, RECLAIM_GRACE_SECS, grace_secs);
+    }
+
+    /// Returns the number of seconds after the end of a vesting before it can be reclaimed.
+    pub fn get_reclaim_grace_secs(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&RECLAIM_GRACE_SECS)
+            .unwrap_or(0)
+    }
+
+    /// Releases the unclaimed tokens of a vesting from the reserve once its reclaim grace period
+    /// has elapsed. Nothing is paid out: withdrawing them goes through the admin withdrawal flow,
+    /// with its delay and approvals. The vesting is drained by setting its claimed amount to its
+    /// final vested amount, so `get_vesting_info` then shows it as fully claimed.
+    pub fn reclaim_abandoned(env: Env, caller: Address, vesting_id: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let grace_secs: u64 = Self::get_reclaim_grace_secs(env.clone());
+        assert!(grace_secs != 0, "Reclaim grace period not set");
+
+        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(
+            !Self::is_irrevocable(env.clone(), vesting_id),
+            "Vesting irrevocable"
+        );
+        assert!(
+            env.ledger().timestamp() > vesting.end_timestamp.saturating_add(grace_secs),
+            "Grace period not elapsed"
+        );
+
+        let final_vest_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.end_timestamp,
+        );
+        let amount_unclaimed = final_vest_amount - vesting.claimed_amount;
+        assert!(amount_unclaimed > 0, "Nothing to reclaim");
+
+        vesting.claimed_amount = final_vest_amount;
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        Self::release_reserved(&env, amount_unclaimed);
+
+        env.events().publish(
+            (VESTING_DRAINED, vesting.recipient.clone(), vesting_id),
+            (vesting_id, vesting.recipient, amount_unclaimed),
+        );
+    }
+
+    /// Sets the minimum number of seconds between two claims of the same vesting, 0 disabling it.
+    pub fn set_claim_cooldown(env: Env, caller: Address, cooldown_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, CLAIM_COOLDOWN, cooldown_secs);
+    }
+
+    /// Returns the minimum number of seconds between two claims of the same vesting.
+    pub fn get_claim_cooldown(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&CLAIM_COOLDOWN).unwrap_or(0)
+    }
+
+    /// Sets the maximum number of vestings a recipient can have, 0 meaning the hard limit of 100.
+    pub fn set_max_vestings_per_recipient(env: Env, caller: Address, max_vestings: u32) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(
+            max_vestings <= MAX_RECIPIENT_VESTINGS,
+            "Invalid max vestings"
+        );
+
+        Self::set_config(&env, MAX_VESTINGS_PER_RECIPIENT, max_vestings);
+    }
+
+    /// Sets the minimum duration in seconds of a new vesting, 0 disabling the check.
+    pub fn set_min_duration_secs(env: Env, caller: Address, min_duration_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, MIN_DURATION_SECS, min_duration_secs);
+    }
+
+    /// Returns the minimum number of seconds between the start and the end of a new vesting.
+    pub fn get_min_duration_secs(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&MIN_DURATION_SECS)
+            .unwrap_or(0)
+    }
+
+    /// Sets the maximum number of seconds the start of a new vesting can be ahead of the ledger
+    /// time, 0 disabling the check.
+    pub fn set_max_future_start_secs(env: Env, caller: Address, max_future_start_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, MAX_FUTURE_START, max_future_start_secs);
+    }
+
+    /// Returns the maximum number of seconds the start of a new vesting can be ahead of the ledger
+    /// time.
+    pub fn get_max_future_start_secs(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&MAX_FUTURE_START).unwrap_or(0)
+    }
+
+    /// Returns the maximum number of vestings a recipient can have, 0 meaning the hard limit of 100.
+    pub fn get_max_vestings_per_recipient(env: Env) -> u32 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&MAX_VESTINGS_PER_RECIPIENT)
+            .unwrap_or(0)
+    }
+
+    /// Allows or disallows vestings whose release interval spans the whole linear duration.
+    pub fn set_single_interval_allowed(env: Env, caller: Address, allowed: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, SINGLE_INTERVAL_ALLOWED, allowed);
+    }
+
+    /// Returns whether a release interval spanning the whole linear duration is allowed.
+    pub fn is_single_interval_allowed(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&SINGLE_INTERVAL_ALLOWED)
+            .unwrap_or(false)
+    }
+
+    /// Pauses or unpauses the claims and the creation of vestings, with an optional reason.
+    pub fn set_claims_paused(env: Env, caller: Address, paused: bool, reason: Option<Symbol>) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(
+            Self::is_claims_paused(env.clone()) != paused,
+            "Flag provided already set"
+        );
+
+        env.storage().instance().set(&CLAIMS_PAUSED, &paused);
+        match reason.clone() {
+            Some(reason) if paused => env.storage().instance().set(&PAUSE_REASON, &reason),
+            _ => env.storage().instance().remove(&PAUSE_REASON),
+        }
+
+        env.events().publish((CLAIMS_PAUSE_SET,), (paused, reason));
+    }
+
+    /// Returns true if the claims of all vestings are paused, false otherwise.
+    pub fn is_claims_paused(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&CLAIMS_PAUSED)
+            .unwrap_or(false)
+    }
+
+    /// Returns the reason given for the global claims pause, if any.
+    pub fn get_pause_reason(env: Env) -> Option<Symbol> {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&PAUSE_REASON)
+    }
+
+    /// Pauses or unpauses the claims of a single vesting.
+    pub fn set_vesting_paused(env: Env, caller: Address, vesting_id: u64, paused: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        // This will panic if there is no vesting associated with a given id.
+        Self::get_vesting_info(env.clone(), vesting_id);
+
+        assert!(
+            Self::vesting_flag(&env, PAUSED_VESTINGS, vesting_id) != paused,
+            "Flag provided already set"
+        );
+
+        Self::set_vesting_flag(&env, PAUSED_VESTINGS, vesting_id, paused);
+
+        env.events()
+            .publish((VESTING_PAUSE_SET,), (vesting_id, paused));
+    }
+
+    /// Returns true if the claims of the given vesting are paused, false otherwise.
+    pub fn is_vesting_paused(env: Env, vesting_id: u64) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        Self::vesting_flag(&env, PAUSED_VESTINGS, vesting_id)
+    }
+
+    /// Sets the number of seconds over which the cliff amount of a vesting vests linearly, 0
+    /// releasing it instantly. Only revocable vestings without claims can be changed.
+    pub fn set_cliff_ramp(env: Env, caller: Address, vesting_id: u64, cliff_ramp_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(
+            !Self::is_irrevocable(env.clone(), vesting_id),
+            "Vesting irrevocable"
+        );
+        assert!(vesting.claimed_amount == 0, "Vesting already claimed");
+        assert!(vesting.cliff_release_timestamp != 0, "No cliff");
+        assert!(
+            env.ledger().timestamp() < vesting.cliff_release_timestamp,
+            "Cliff already released"
+        );
+        assert!(
+            cliff_ramp_secs <= vesting.end_timestamp - vesting.cliff_release_timestamp,
+            "Invalid cliff ramp"
+        );
+
+        Self::record_cliff_ramp(&env, vesting_id, cliff_ramp_secs);
+
+        env.events()
+            .publish((CLIFF_RAMP_SET,), (vesting_id, cliff_ramp_secs));
+    }
+
+    /// Returns the number of seconds over which the cliff amount of a vesting vests linearly.
+    pub fn get_cliff_ramp(env: Env, vesting_id: u64) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        let cliff_ramps: Map<u64, u64> = env
+            .storage()
+            .persistent()
+            .get(&CLIFF_RAMPS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        cliff_ramps.get(vesting_id).unwrap_or(0)
+    }
+
+    /// Calculates the vested amount for a given Vesting, at a given timestamp, with the cliff
+    /// amount released at once. Use `calculate_ramped_vested_amount` for a vesting with a
+    /// cliff ramp.
+    pub fn calculate_vested_amount(env: Env, vesting: Vesting, reference_timestamp: u64) -> i128 {
+        Self::calculate_ramped_vested_amount(env, vesting, reference_timestamp, 0)
+    }
+
+    /// Calculates the vested amount for a given Vesting, at a given timestamp, with the cliff
+    /// amount vesting linearly over `cliff_ramp_secs` from the cliff release timestamp.
+    /// The ramp of a stored vesting is returned by `get_cliff_ramp`.
+    pub fn calculate_ramped_vested_amount(
+        _env: Env,
+        vesting: Vesting,
+        reference_timestamp: u64,
+        cliff_ramp_secs: u64,
+    ) -> i128 {
+        let mut adjusted_reference_timestamp = reference_timestamp;
+
+        if vesting.deactivation_timestamp != 0
+            && adjusted_reference_timestamp > vesting.deactivation_timestamp
+        {
+            adjusted_reference_timestamp = vesting.deactivation_timestamp;
+        }
+
+        let mut vesting_amount: i128 = 0;
+
+        if adjusted_reference_timestamp >= vesting.end_timestamp {
+            adjusted_reference_timestamp = vesting.end_timestamp;
+        }
+
+        if adjusted_reference_timestamp >= vesting.cliff_release_timestamp {
+            let cliff_elapsed_secs = adjusted_reference_timestamp - vesting.cliff_release_timestamp;
+
+            if cliff_elapsed_secs >= cliff_ramp_secs {
+                vesting_amount += vesting.cliff_amount;
+            } else {
+                vesting_amount += vesting
+                    .cliff_amount
+                    .checked_mul(cliff_elapsed_secs as i128)
+                    .unwrap_or_else(|| panic!("Amount overflow"))
+                    / cliff_ramp_secs as i128;
+            }
+        }
+
+        if vesting.initial_unlock > 0 && adjusted_reference_timestamp >= vesting.start_timestamp {
+            vesting_amount += vesting.initial_unlock;
+        }
+
+        let start_timestamp: u64 = if vesting.cliff_release_timestamp != 0 {
+            vesting.cliff_release_timestamp
+        } else {
+            vesting.start_timestamp
+        };
+
+        if adjusted_reference_timestamp > start_timestamp {
+            assert!(
+                vesting.release_interval_secs != 0,
+                "Invalid release interval"
+            );
+
+            // Cannot underflow given the branch condition.
+            let current_vesting_duration_secs = adjusted_reference_timestamp - start_timestamp;
+            let truncated_current_vesting_duration_secs = (current_vesting_duration_secs
+                / vesting.release_interval_secs)
+                * vesting.release_interval_secs;
+
+            let final_vesting_duration_secs: i128 = vesting
+                .end_timestamp
+                .checked_sub(start_timestamp)
+                .unwrap_or_else(|| panic!("Invalid vesting duration"))
+                .into();
+
+            let truncated_current_vesting_duration_secs: i128 =
+                truncated_current_vesting_duration_secs.into();
+
+            let mut linear_vest_amount: i128;
+
+            if final_vesting_duration_secs == truncated_current_vesting_duration_secs {
+                linear_vest_amount = vesting.linear_vest_amount;
+            } else {
+                // Dividing before multiplying keeps the rounding error under one base unit
+                // regardless of the token decimals, without risking overflow.
+                let number_of_intervals: i128 =
+                    final_vesting_duration_secs / vesting.release_interval_secs as i128;
+                assert!(number_of_intervals != 0, "Invalid release interval");
+                let tokens_per_interval: i128 = vesting.linear_vest_amount / number_of_intervals;
+                let current_intervals: i128 =
+                    truncated_current_vesting_duration_secs / vesting.release_interval_secs as i128;
+
+                linear_vest_amount = tokens_per_interval * current_intervals;
+                let remainder = vesting.linear_vest_amount % number_of_intervals;
+                let remainder_distribution = (remainder * current_intervals) / number_of_intervals;
+
+                linear_vest_amount += remainder_distribution;
+            }
+
+            vesting_amount += linear_vest_amount;
+        }
+
+        vesting_amount
+    }
+
+    /// Returns the amount the recipient could claim from a vesting at the given timestamp.
+    pub fn get_claimable_amount(env: Env, vesting_id: u64, reference_timestamp: u64) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+
+        Self::claimable_amount(env, vesting_id, vesting, reference_timestamp)
+    }
+
+    /// Returns the amount of a vesting still unvested at the given timestamp.
+    pub fn get_unvested_amount(env: Env, vesting_id: u64, reference_timestamp: u64) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+
+        let final_vest_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.end_timestamp,
+        );
+
+        final_vest_amount - Self::vested_amount(env, vesting_id, vesting, reference_timestamp)
+    }
+
+    /// Returns the total of tokens paid out to a recipient by claims across all their vestings.
+    pub fn get_recipient_claimed_total(env: Env, recipient: Address) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        let recipient_claimed: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_CLAIMED)
+            .unwrap_or_else(|| Map::new(&env));
+
+        recipient_claimed.get(recipient).unwrap_or(0)
+    }
+
+    /// Allows the admin to deposit tokens into the contract without creating a vesting.
+    pub fn deposit(env: Env, caller: Address, amount: i128) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(amount > 0, "Invalid amount");
+
+        Self::transfer_from_caller(env.clone(), caller.clone(), amount);
+
+        env.events().publish((DEPOSITED,), (caller, amount));
+    }
+
+    /// Allows the admin to withdraw ERC20 tokens not locked in vesting.
+    pub fn withdraw_admin(env: Env, caller: Address, amount_requested: i128) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(
+            Self::get_withdraw_delay(env.clone()) == 0,
+            "Withdrawal delay enabled"
+        );
+
+        // Approval check
+        Self::consume_action_approvals(
+            env.clone(),
+            withdraw_action_hash(&env, caller.clone(), amount_requested),
+        );
+
+        let amount_remaining = Self::amount_to_withdraw_by_admin(env.clone());
+        assert!(amount_remaining >= amount_requested, "Insufficient balance");
+
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+
+        TokenClient::new(&env, &token_address).transfer(
+            &env.current_contract_address(),
+            &caller,
+            &amount_requested,
+        );
+
+        env.events()
+            .publish((ADMIN_WITHDRAWN,), (caller, amount_requested));
+    }
+
+    /// Sets the delay in seconds between requesting and executing an admin withdrawal. Decreases
+    /// apply after the current delay.
+    pub fn set_withdraw_delay(env: Env, caller: Address, delay_secs: u64) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_delay(&env, WITHDRAW_DELAY, PENDING_WITHDRAW_DELAY, delay_secs);
+    }
+
+    /// Returns the pending decrease of the withdrawal delay and when it can be applied, if any.
+    pub fn get_pending_withdraw_delay(env: Env) -> Option<(u64, u64)> {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&PENDING_WITHDRAW_DELAY)
+    }
+
+    /// Returns the number of seconds between requesting and executing an admin withdrawal.
+    pub fn get_withdraw_delay(env: Env) -> u64 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&WITHDRAW_DELAY).unwrap_or(0)
+    }
+
+    /// Requests an admin withdrawal, executable once the withdrawal delay has elapsed.
+    pub fn request_withdraw(env: Env, caller: Address, amount: i128) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(amount > 0, "Invalid amount");
+        assert!(
+            !env.storage().instance().has(&PENDING_WITHDRAW),
+            "Withdrawal already pending"
+        );
+
+        // Approval check
+        Self::consume_action_approvals(
+            env.clone(),
+            withdraw_action_hash(&env, caller.clone(), amount),
+        );
+
+        let ready_timestamp = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_withdraw_delay(env.clone()));
+
+        let pending_withdrawal = PendingWithdrawal {
+            requester: caller.clone(),
+            amount,
+            ready_timestamp,
+        };
+        env.storage()
+            .instance()
+            .set(&PENDING_WITHDRAW, &pending_withdrawal);
+
+        env.events()
+            .publish((WITHDRAW_REQUESTED,), (caller, amount, ready_timestamp));
+    }
+
+    /// Executes the pending admin withdrawal once its delay has elapsed.
+    pub fn execute_withdraw(env: Env, caller: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let pending_withdrawal = Self::get_pending_withdrawal(env.clone());
+        assert!(
+            env.ledger().timestamp() >= pending_withdrawal.ready_timestamp,
+            "Withdrawal delay not elapsed"
+        );
+
+        let amount_remaining = Self::amount_to_withdraw_by_admin(env.clone());
+        assert!(
+            amount_remaining >= pending_withdrawal.amount,
+            "Insufficient balance"
+        );
+
+        env.storage().instance().remove(&PENDING_WITHDRAW);
+
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+
+        TokenClient::new(&env, &token_address).transfer(
+            &env.current_contract_address(),
+            &pending_withdrawal.requester,
+            &pending_withdrawal.amount,
+        );
+
+        env.events().publish(
+            (ADMIN_WITHDRAWN,),
+            (pending_withdrawal.requester, pending_withdrawal.amount),
+        );
+    }
+
+    /// Cancels the pending admin withdrawal.
+    pub fn cancel_withdraw(env: Env, caller: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let pending_withdrawal = Self::get_pending_withdrawal(env.clone());
+
+        env.storage().instance().remove(&PENDING_WITHDRAW);
+
+        env.events()
+            .publish((WITHDRAW_CANCELLED,), (caller, pending_withdrawal.amount));
+    }
+
+    /// Returns the pending admin withdrawal.
+    pub fn get_pending_withdrawal(env: Env) -> PendingWithdrawal {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&PENDING_WITHDRAW)
+            .unwrap_or_else(|| panic!("No pending withdrawal"))
+    }
+
+    /// Withdraws other ERC20 tokens accidentally sent to the contract's address.
+    pub fn withdraw_other_token(env: Env, caller: Address, other_token_address: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::withdraw_other_token_internal(env, caller, other_token_address);
+    }
+
+    /// Returns the amount of tokens that are available for the admin to withdraw.
+    pub fn amount_to_withdraw_by_admin(env: Env) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        let balance = Self::get_balance(env.clone());
+
+        let reserved_tokens: i128 = env
+            .storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0);
+
+        balance - reserved_tokens
+    }
+
+    /// Returns the balance of the vested token held by the contract.
+    pub fn get_balance(env: Env) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+
+        TokenClient::new(&env, &token_address).balance(&env.current_contract_address())
+    }
+
+    /// Sets whether recipients must acknowledge their vesting with `acknowledge` before claiming.
+    pub fn set_require_ack(env: Env, caller: Address, required: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, REQUIRE_ACK, required);
+    }
+
+    /// Returns whether recipients must acknowledge their vesting before claiming.
+    pub fn is_ack_required(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&REQUIRE_ACK).unwrap_or(false)
+    }
+
+    /// Sunsets the contract, permanently blocking the creation of vestings.
+    pub fn sunset(env: Env, caller: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        assert!(!Self::is_sunset(env.clone()), "Contract already sunset");
+
+        env.storage().instance().set(&SUNSET, &true);
+
+        env.events()
+            .publish((SUNSET_SET,), (caller, env.ledger().timestamp()));
+    }
+
+    /// Returns whether the contract is sunset.
+    pub fn is_sunset(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&SUNSET).unwrap_or(false)
+    }
+
+    /// Enables or disables the recipient allowlist for new vestings.
+    pub fn set_recipient_allowlist_enabled(env: Env, caller: Address, enabled: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, RECIPIENT_ALLOWLIST_ENABLED, enabled);
+    }
+
+    /// Returns whether the recipient allowlist is enabled.
+    pub fn is_recipient_allowlist_enabled(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&RECIPIENT_ALLOWLIST_ENABLED)
+            .unwrap_or(false)
+    }
+
+    /// Adds a recipient to or removes a recipient from the allowlist.
+    pub fn set_recipient_allowed(env: Env, caller: Address, recipient: Address, allowed: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let mut recipient_allowlist: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_ALLOWLIST)
+            .unwrap_or_else(|| Map::new(&env));
+
+        if allowed {
+            recipient_allowlist.set(recipient.clone(), true);
+        } else {
+            recipient_allowlist.remove(recipient.clone());
+        }
+        env.storage()
+            .persistent()
+            .set(&RECIPIENT_ALLOWLIST, &recipient_allowlist);
+        Self::extend_persistent_ttl(&env, RECIPIENT_ALLOWLIST);
+
+        env.events()
+            .publish((RECIPIENT_ALLOWED_SET,), (recipient, allowed));
+    }
+
+    /// Returns whether new vestings can be created for the recipient.
+    pub fn is_recipient_allowed(env: Env, recipient: Address) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        if !Self::is_recipient_allowlist_enabled(env.clone()) {
+            return true;
+        }
+
+        let recipient_allowlist: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_ALLOWLIST)
+            .unwrap_or_else(|| Map::new(&env));
+
+        recipient_allowlist.get(recipient).unwrap_or(false)
+    }
+
+    /// Enables or disables blocking admins from being vesting recipients.
+    pub fn set_block_admin_recipients(env: Env, caller: Address, blocked: bool) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        Self::set_config(&env, BLOCK_ADMIN_RECIPIENTS, blocked);
+    }
+
+    /// Returns whether admins are blocked from being vesting recipients.
+    pub fn is_admin_recipients_blocked(env: Env) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&BLOCK_ADMIN_RECIPIENTS)
+            .unwrap_or(false)
+    }
+
+    /// Acknowledges the terms of a vesting by its recipient.
+    pub fn acknowledge(env: Env, caller: Address, vesting_id: u64) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let vesting = Self::get_vesting_info(env.clone(), vesting_id);
+
+        // Access control check
+        caller.require_auth();
+        if vesting.recipient != caller {
+            panic!("Not vesting owner");
+        }
+
+        assert!(
+            !Self::is_acknowledged(env.clone(), vesting_id),
+            "Vesting already acknowledged"
+        );
+
+        Self::set_vesting_flag(&env, ACKNOWLEDGED_VESTINGS, vesting_id, true);
+
+        env.events()
+            .publish((VESTING_ACKNOWLEDGED,), (vesting_id, caller));
+    }
+
+    /// Returns true if the recipient has acknowledged the given vesting, false otherwise.
+    pub fn is_acknowledged(env: Env, vesting_id: u64) -> bool {
+        Self::extend_instance_ttl(&env);
+
+        Self::vesting_flag(&env, ACKNOWLEDGED_VESTINGS, vesting_id)
+    }
+
+    /// Sets or clears the address the claimed tokens of a recipient must be sent to.
+    pub fn set_mandatory_destination(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        destination: Option<Address>,
+    ) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let mut mandatory_destinations: Map<Address, Address> = env
+            .storage()
+            .persistent()
+            .get(&MANDATORY_DESTINATIONS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        match destination.clone() {
+            Some(destination) => mandatory_destinations.set(recipient.clone(), destination),
+            None => {
+                mandatory_destinations.remove(recipient.clone());
+            }
+        }
+        env.storage()
+            .persistent()
+            .set(&MANDATORY_DESTINATIONS, &mandatory_destinations);
+        Self::extend_persistent_ttl(&env, MANDATORY_DESTINATIONS);
+
+        env.events()
+            .publish((MANDATORY_DESTINATION_SET,), (recipient, destination));
+    }
+
+    /// Returns the address the claimed tokens of a recipient must be sent to, if any.
+    pub fn get_mandatory_destination(env: Env, recipient: Address) -> Option<Address> {
+        Self::extend_instance_ttl(&env);
+
+        let mandatory_destinations: Map<Address, Address> = env
+            .storage()
+            .persistent()
+            .get(&MANDATORY_DESTINATIONS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        mandatory_destinations.get(recipient)
+    }
+
+    /// Sets or clears the hook contract called with `on_claim(recipient, amount)` after each claim
+    /// of the caller, including the amounts pushed by `create_and_push_initial` and
+    /// `revoke_and_settle`.
+    pub fn set_claim_hook(env: Env, caller: Address, hook: Option<Address>) {
+        Self::extend_instance_ttl(&env);
+
+        // Access control check
+        caller.require_auth();
+
+        let claim_hook_entry = (CLAIM_HOOKS, caller.clone());
+        match hook.clone() {
+            Some(hook) => {
+                env.storage().persistent().set(&claim_hook_entry, &hook);
+                env.storage().persistent().extend_ttl(
+                    &claim_hook_entry,
+                    LIFETIME_THRESHOLD,
+                    EXTENSION_AMOUNT,
+                );
+            }
+            None => env.storage().persistent().remove(&claim_hook_entry),
+        }
+
+        env.events().publish((CLAIM_HOOK_SET,), (caller, hook));
+    }
+
+    /// Returns the claim hook contract of a recipient, if any.
+    pub fn get_claim_hook(env: Env, recipient: Address) -> Option<Address> {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().persistent().get(&(CLAIM_HOOKS, recipient))
+    }
+
+    /// Returns the address that funded a vesting.
+    pub fn get_vesting_funder(env: Env, vesting_id: u64) -> Address {
+        Self::extend_instance_ttl(&env);
+
+        let vesting_funders: Map<u64, Address> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_FUNDERS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        vesting_funders
+            .get(vesting_id)
+            .unwrap_or_else(|| panic!("Vesting funder not found"))
+    }
+
+    /// Retrieves information about a specific vesting arrangement.
+    pub fn get_vesting_info(env: Env, vesting_id: u64) -> Vesting {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let vesting_by_id: Map<u64, Vesting> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_BY_ID)
+            .unwrap_or_else(|| Map::new(&env));
+
+        vesting_by_id
+            .get(vesting_id)
+            .unwrap_or_else(|| panic!("Vesting not found"))
+    }
+
+    /// Retrieves information about a specific vesting arrangement, or `None` if it does not exist.
+    pub fn get_vesting_info_or_default(env: Env, vesting_id: u64) -> Option<Vesting> {
+        Self::extend_instance_ttl(&env);
+
+        let vesting_by_id: Map<u64, Vesting> = env.storage().persistent().get(&VESTING_BY_ID)?;
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        vesting_by_id.get(vesting_id)
+    }
+
+    /// Returns whether the contract is initialized, for uptime monitors. Never panics.
+    pub fn health(env: Env) -> bool {
+        env.storage().persistent().has(&ADMINS)
+    }
+
+    /// Returns all recipient addresses which have at least one vesting schedule set.
+    /// The result is unbounded: use `get_all_recipients_bounded` or `get_all_recipients_sliced`
+    /// when there may be more than 100 of them.
+    pub fn get_all_recipients(env: Env) -> Vec<Address> {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+
+        env.storage()
+            .persistent()
+            .get(&RECIPIENTS)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns at most the first 100 recipient addresses which have at least one vesting schedule
+    /// set, and true if there are more of them, read with `get_all_recipients_sliced`.
+    pub fn get_all_recipients_bounded(env: Env) -> (Vec<Address>, bool) {
+        let recipients = Self::get_all_recipients(env);
+        if recipients.len() > MAX_RECIPIENTS_PAGE {
+            return (recipients.slice(0..MAX_RECIPIENTS_PAGE), true);
+        }
+
+        (recipients, false)
+    }
+
+    /// Returns the list of recipients in a specific range, `from` being inclusive and `to` being
+    /// exclusive, spanning at most 100.
+    pub fn get_all_recipients_sliced(env: Env, from: u32, to: u32) -> Vec<Address> {
+        Self::extend_instance_ttl(&env);
+
+        assert!(
+            to.saturating_sub(from) <= MAX_RECIPIENTS_PAGE,
+            "Range too large"
+        );
+
+        let recipients: Vec<Address> = match env.storage().persistent().get(&RECIPIENTS) {
+            Some(recipients) => recipients,
+            None => return Vec::new(&env),
+        };
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+
+        let to = to.min(recipients.len());
+        if from >= to {
+            return Vec::new(&env);
+        }
+
+        recipients.slice(from..to)
+    }
+
+    /// Returns the number of recipients.
+    pub fn get_all_recipients_len(env: Env) -> u32 {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+
+        let recipients: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENTS)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        recipients.len()
+    }
+
+    /// Returns the list of vestings for the recipient, at most 100 vestings.
+    pub fn get_all_recipient_vestings(env: Env, recipient: Address) -> Vec<u64> {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+
+        let recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        recipient_vestings
+            .get(recipient)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns the list of vestings for the recipient in a specific range, `from` being inclusive
+    /// and `to` being exclusive.
+    pub fn get_all_recipient_vesting_sliced(
+        env: Env,
+        from: u32,
+        to: u32,
+        recipient: Address,
+    ) -> Vec<u64> {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+
+        let recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let vestings: Vec<u64> = match recipient_vestings.get(recipient) {
+            Some(vestings) if !vestings.is_empty() => vestings,
+            _ => return Vec::new(&env),
+        };
+
+        let to = to.min(vestings.len());
+        let from = from.min(to);
+
+        vestings.slice(from..to)
+    }
+
+    /// Returns the length of all vestings for the recipient.
+    pub fn get_all_recipient_vestings_len(env: Env, recipient: Address) -> u32 {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+
+        let recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        recipient_vestings
+            .get(recipient)
+            .unwrap_or_else(|| Vec::new(&env))
+            .len()
+    }
+
+    /// Checks if a given address is a recipient of any vesting schedule.
+    pub fn is_recipient(env: Env, recipient: Address) -> bool {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+
+        let recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let recipient_ids: Vec<u64> = recipient_vestings
+            .get(recipient)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        !recipient_ids.is_empty()
+    }
+
+    /// Replaces the token used by the contract while no vesting exists and it holds none of the
+    /// current token.
+    pub fn reconfigure_token(env: Env, caller: Address, new_token: Address) {
+        // Access control check
+        Self::require_admin(&env, &caller);
+
+        let old_token: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+        assert!(old_token != new_token, "Same token");
+
+        let nonce: u64 = env.storage().instance().get(&NONCE).unwrap_or(0);
+        assert!(nonce == 0, "Vestings already created");
+        assert!(
+            TokenClient::new(&env, &old_token).balance(&env.current_contract_address()) == 0,
+            "Contract already funded"
+        );
+
+        env.storage().instance().set(&TOKEN_ADDRESS, &new_token);
+
+        env.events()
+            .publish((TOKEN_RECONFIGURED,), (old_token, new_token));
+    }
+
+    /// Returns the address of the token used in the vesting contract.
+    pub fn get_token_address(env: Env) -> Address {
+        Self::extend_instance_ttl(&env);
+
+        env.storage().instance().get(&TOKEN_ADDRESS).unwrap()
+    }
+
+    /// Returns the amount of token reserved for vesting in the contract.
+    pub fn get_tokens_reserved_for_vesting(env: Env) -> i128 {
+        Self::extend_instance_ttl(&env);
+
+        env.storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0)
+    }
+
+    /// Returns the reason a vesting cannot be claimed now, 0 if it can, and its claimable amount.
+    fn claim_skip_reason(env: &Env, vesting_id: u64, vesting: &Vesting) -> (u32, i128) {
+        if vesting.timelock > env.ledger().timestamp() {
+            return (SKIP_TIMELOCKED, 0);
+        }
+        if Self::is_vesting_paused(env.clone(), vesting_id) {
+            return (SKIP_PAUSED, 0);
+        }
+        if !Self::acknowledgement_satisfied(env.clone(), vesting_id) {
+            return (SKIP_NOT_ACKNOWLEDGED, 0);
+        }
+
+        let claimable = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            env.ledger().timestamp(),
+        ) - vesting.claimed_amount;
+
+        if claimable <= 0 {
+            return (SKIP_NOTHING_TO_CLAIM, 0);
+        }
+        if !Self::claim_cooldown_elapsed(env.clone(), vesting_id, vesting.clone(), claimable) {
+            return (SKIP_COOLDOWN, claimable);
+        }
+        (0, claimable)
+    }
+
+    /// Checks that a vesting can be claimed by the caller and marks its claimable amount as
+    /// claimed, releasing it from the reserve. Returns that amount and the tokens left reserved.
+    /// Recording and transferring the payout is left to the caller.
+    fn settle_claim(env: Env, caller: Address, vesting_id: u64) -> (i128, i128) {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
+
+        // Access control check
+        caller.require_auth();
+        if vesting.recipient != caller {
+            panic!("Not vesting owner");
+        }
+
+        assert!(!Self::is_claims_paused(env.clone()), "Claims paused");
+
+        let (skip_reason, claimable) = Self::claim_skip_reason(&env, vesting_id, &vesting);
+        match skip_reason {
+            SKIP_TIMELOCKED => panic!("Timelock enabled"),
+            SKIP_PAUSED => panic!("Vesting paused"),
+            SKIP_NOT_ACKNOWLEDGED => panic!("Vesting not acknowledged"),
+            SKIP_NOTHING_TO_CLAIM => panic!("Insufficient balance to claim"),
+            SKIP_COOLDOWN => panic!("Claim cooldown active"),
+            _ => {}
+        }
+        Self::record_claim_timestamp(env.clone(), vesting_id);
+
+        vesting.claimed_amount += claimable;
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        let reserved_tokens = Self::release_reserved(&env, claimable);
+
+        (claimable, reserved_tokens)
+    }
+
+    /// Internal version of `create_vesting`, used for `create_vesting_batch`.
+    /// Same but without authentication, required to make `create_vesting_batch` work properly.
+    ///
+    /// Creates a vesting schedule for a recipient and returns a vesting ID.
+    fn create_vesting_internal(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> u64 {
+        let vesting_id = Self::record_vesting(
+            env.clone(),
+            caller.clone(),
+            recipient,
+            start_timestamp,
+            end_timestamp,
+            timelock,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+            0,
+        );
+
+        // The amounts were checked against overflow when recording the vesting.
+        Self::transfer_from_caller(
+            env,
+            caller,
+            initial_unlock + cliff_amount + linear_vest_amount,
+        );
+
+        vesting_id
+    }
+
+    /// Records a vesting schedule funded by the caller, with the given amount already claimed, and
+    /// returns a vesting ID.
+    /// No tokens are pulled in: the caller must fund the vesting or already hold the tokens
+    /// in the contract.
+    fn record_vesting(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+        claimed_amount: i128,
+    ) -> u64 {
+        Self::extend_instance_ttl(&env);
+        Self::extend_persistent_ttl(&env, RECIPIENTS);
+        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+
+        let total_expected_amount = Self::validate_vesting_params(
+            env.clone(),
+            recipient.clone(),
+            start_timestamp,
+            end_timestamp,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+        );
+
+        let reserved_tokens = env
+            .storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0_i128)
+            .checked_add(total_expected_amount)
+            .unwrap_or_else(|| panic!("Reserved overflow"));
+
+        env.storage()
+            .instance()
+            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+
+        let vesting: Vesting = Vesting {
+            recipient: recipient.clone(),
+            start_timestamp,
+            end_timestamp,
+            deactivation_timestamp: 0,
+            timelock,
+            release_interval_secs,
+            cliff_release_timestamp,
+            initial_unlock,
+            cliff_amount,
+            linear_vest_amount,
+            claimed_amount,
+        };
+
+        let vesting_id: u64 = env.storage().instance().get(&NONCE).unwrap_or(0);
+        let new_vesting_id: u64 = vesting_id + 1;
+        env.storage().instance().set(&NONCE, &new_vesting_id);
+
+        if !Self::is_recipient(env.clone(), recipient.clone()) {
+            let mut recipients: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&RECIPIENTS)
+                .unwrap_or_else(|| Vec::new(&env));
+
+            recipients.push_back(recipient.clone());
+            env.storage().persistent().set(&RECIPIENTS, &recipients);
+        }
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        let mut recipient_vestings: Map<Address, Vec<u64>> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_VESTINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut recipient_ids: Vec<u64> = recipient_vestings
+            .get(recipient.clone())
+            .unwrap_or_else(|| Vec::new(&env));
+        recipient_ids.push_back(vesting_id);
+        recipient_vestings.set(recipient.clone(), recipient_ids);
+
+        env.storage()
+            .persistent()
+            .set(&RECIPIENT_VESTINGS, &recipient_vestings);
+
+        let mut vesting_funders: Map<u64, Address> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_FUNDERS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        vesting_funders.set(vesting_id, caller.clone());
+        env.storage()
+            .persistent()
+            .set(&VESTING_FUNDERS, &vesting_funders);
+        Self::extend_persistent_ttl(&env, VESTING_FUNDERS);
+
+        env.events().publish(
+            (VESTING_CREATED, recipient.clone(), vesting_id),
+            (vesting_id, recipient, vesting, reserved_tokens),
+        );
+
+        vesting_id
+    }
+
+    /// Validates the parameters of a new vesting against the contract configuration, and returns
+    /// the total amount of tokens it requires. Does not change any state.
+    fn validate_vesting_params(
+        env: Env,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> i128 {
+        assert!(!Self::is_sunset(env.clone()), "Contract sunset");
+        assert!(!Self::is_claims_paused(env.clone()), "Claims paused");
+        assert!(
+            initial_unlock >= 0 && cliff_amount >= 0 && linear_vest_amount >= 0,
+            "Invalid amount"
+        );
+        assert!(
+            linear_vest_amount != 0 || cliff_amount != 0,
+            "Invalid vested amount"
+        );
+        assert!(
+            start_timestamp != 0 && start_timestamp < end_timestamp,
+            "Invalid start timestamp"
+        );
+        assert!(
+            end_timestamp - start_timestamp >= Self::get_min_duration_secs(env.clone()),
+            "Vesting duration too short"
+        );
+        let max_future_start_secs = Self::get_max_future_start_secs(env.clone());
+        assert!(
+            max_future_start_secs == 0
+                || start_timestamp
+                    <= env
+                        .ledger()
+                        .timestamp()
+                        .saturating_add(max_future_start_secs),
+            "Start timestamp too far in the future"
+        );
+        assert!(release_interval_secs != 0, "Invalid release interval");
+
+        if cliff_release_timestamp == 0 {
+            assert!(cliff_amount == 0, "invalid cliff amount");
+            assert!(
+                (end_timestamp - start_timestamp).is_multiple_of(release_interval_secs),
+                "Invalid interval length"
+            );
+        } else {
+            assert!(cliff_amount != 0, "Invalid cliff amount");
+            assert!(
+                start_timestamp <= cliff_release_timestamp
+                    && cliff_release_timestamp < end_timestamp,
+                "Invalid cliff release"
+            );
+            assert!(
+                (end_timestamp - cliff_release_timestamp).is_multiple_of(release_interval_secs),
+                "Invalid interval length"
+            );
+        }
+
+        // A single interval releases the whole linear amount at once at the end timestamp,
+        // which is only accepted when explicitly allowed.
+        let linear_start_timestamp = if cliff_release_timestamp == 0 {
+            start_timestamp
+        } else {
+            cliff_release_timestamp
+        };
+        if linear_vest_amount != 0
+            && release_interval_secs >= end_timestamp - linear_start_timestamp
+        {
+            assert!(
+                env.storage()
+                    .instance()
+                    .get(&SINGLE_INTERVAL_ALLOWED)
+                    .unwrap_or(false),
+                "Single release interval not allowed"
+            );
+        }
+
+        assert!(
+            Self::is_recipient_allowed(env.clone(), recipient.clone()),
+            "Recipient not allowed"
+        );
+        assert!(
+            !Self::is_admin_recipients_blocked(env.clone())
+                || !Self::is_admin(env.clone(), recipient.clone()),
+            "Recipient is an admin"
+        );
+
+        assert!(
+            Self::get_all_recipient_vestings_len(env.clone(), recipient.clone())
+                < Self::recipient_vestings_limit(&env),
+            "Too many vestings for recipient"
+        );
+
+        initial_unlock
+            .checked_add(cliff_amount)
+            .and_then(|amount| amount.checked_add(linear_vest_amount))
+            .unwrap_or_else(|| panic!("Amount overflow"))
+    }
+
+    /// Pulls tokens from the caller into the contract.
+    ///
+    /// The contract is the spender of the transfer, so the caller must have approved
+    /// the contract for at least `amount` beforehand.
+    fn transfer_from_caller(env: Env, caller: Address, amount: i128) {
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+        let token_client = TokenClient::new(&env, &token_address);
+
+        assert!(
+            token_client.allowance(&caller, &env.current_contract_address()) >= amount,
+            "Insufficient allowance"
+        );
+
+        token_client.transfer_from(
+            &env.current_contract_address(),
+            &caller,
+            &env.current_contract_address(),
+            &amount,
+        );
+    }
+
+    /// Releases an amount from the tokens reserved for vesting, returning the remaining reserve.
+    fn release_reserved(env: &Env, amount: i128) -> i128 {
+        let reserved_tokens: i128 = env
+            .storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0)
+            - amount;
+
+        env.storage()
+            .instance()
+            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+
+        reserved_tokens
+    }
+
+    /// Stores a vesting under its id.
+    fn store_vesting(env: &Env, vesting_id: u64, vesting: &Vesting) {
+        let mut vesting_by_id: Map<u64, Vesting> = env
+            .storage()
+            .persistent()
+            .get(&VESTING_BY_ID)
+            .unwrap_or_else(|| Map::new(env));
+
+        vesting_by_id.set(vesting_id, vesting.clone());
+        env.storage()
+            .persistent()
+            .set(&VESTING_BY_ID, &vesting_by_id);
+    }
+
+    /// Stores the cliff ramp of a vesting, removing it when 0.
+    fn record_cliff_ramp(env: &Env, vesting_id: u64, cliff_ramp_secs: u64) {
+        let mut cliff_ramps: Map<u64, u64> = env
+            .storage()
+            .persistent()
+            .get(&CLIFF_RAMPS)
+            .unwrap_or_else(|| Map::new(env));
+
+        if cliff_ramp_secs == 0 {
+            cliff_ramps.remove(vesting_id);
+        } else {
+            cliff_ramps.set(vesting_id, cliff_ramp_secs);
+        }
+        env.storage().persistent().set(&CLIFF_RAMPS, &cliff_ramps);
+        Self::extend_persistent_ttl(env, CLIFF_RAMPS);
+    }
+
+    /// Transfers claimed tokens out of the contract. The claimed amount and the tokens reserved
+    /// for vesting are already settled, so a token moving any other amount, e.g. by charging a
+    /// transfer fee, reverts the claim rather than leaving the accounting inconsistent.
+    fn transfer_claimed(env: &Env, destination: Address, amount: i128) {
+        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+        let token_client = TokenClient::new(env, &token_address);
+
+        let balance_before = token_client.balance(&env.current_contract_address());
+        token_client.transfer(&env.current_contract_address(), &destination, &amount);
+        let balance_after = token_client.balance(&env.current_contract_address());
+
+        assert!(
+            balance_before - balance_after == amount,
+            "Token transfer amount mismatch"
+        );
+    }
+
+    /// Internal version of `revoke_vesting`, shared by the revocation entrypoints.
+    /// Same but without authentication.
+    ///
+    /// Revokes a vesting arrangement and returns the unvested amount released from the reserve.
+    fn revoke_vesting_internal(env: Env, vesting_id: u64) -> i128 {
+        let mut vesting = Self::get_vesting_info(env.clone(), vesting_id);
+        assert!(vesting.deactivation_timestamp == 0, "Vesting not active");
+        assert!(
+            !Self::is_irrevocable(env.clone(), vesting_id),
+            "Vesting irrevocable"
+        );
+
+        // Full vested total, computed before the deactivation timestamp caps the schedule.
+        let final_vest_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.end_timestamp,
+        );
+        assert!(
+            final_vest_amount != vesting.claimed_amount,
+            "All vested amount already claimed"
+        );
+
+        vesting.deactivation_timestamp = env.ledger().timestamp();
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        let vested_amount_at_deactivation = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.deactivation_timestamp,
+        );
+        let amount_remaining = final_vest_amount
+            .checked_sub(vested_amount_at_deactivation)
+            .unwrap_or_else(|| panic!("Amount underflow"));
+
+        let reserved_tokens = env
+            .storage()
+            .instance()
+            .get(&TOKENS_RESERVED_FOR_VESTING)
+            .unwrap_or(0_i128)
+            .checked_sub(amount_remaining)
+            .unwrap_or_else(|| panic!("Reserved underflow"));
+
+        env.storage()
+            .instance()
+            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+
+        env.events().publish(
+            (VESTING_REVOKED, vesting.recipient.clone(), vesting_id),
+            (
+                vesting_id,
+                vesting.clone().recipient,
+                amount_remaining,
+                vesting,
+                reserved_tokens,
+            ),
+        );
+
+        amount_remaining
+    }
+
+    /// Transfers the full balance of another token held by the contract to the caller.
+    fn withdraw_other_token_internal(env: Env, caller: Address, other_token_address: Address) {
+        assert!(
+            other_token_address != Self::get_token_address(env.clone()),
+            "Invalid other token"
+        );
+
+        let balance =
+            TokenClient::new(&env, &other_token_address).balance(&env.current_contract_address());
+
+        TokenClient::new(&env, &other_token_address).transfer(
+            &env.current_contract_address(),
+            &caller,
+            &balance,
+        );
+
+        env.events()
+            .publish((ADMIN_WITHDRAWN_OTHER,), (caller, balance));
+    }
 
-        recipient_vestings
-            .get(recipient)
-            .unwrap_or_else(|| Vec::new(&env))
-            .len()
+    /// Returns the address the claimed tokens of a recipient are sent to.
+    fn payout_destination(env: Env, recipient: Address) -> Address {
+        Self::get_mandatory_destination(env, recipient.clone()).unwrap_or(recipient)
     }
 
-    /// Checks if a given address is a recipient of any vesting schedule.
-    pub fn is_recipient(env: Env, recipient: Address) -> bool {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
+    /// Returns the number of vestings a recipient can have: the configured maximum, or the hard
+    /// limit when no maximum is set.
+    fn recipient_vestings_limit(env: &Env) -> u32 {
+        match Self::get_max_vestings_per_recipient(env.clone()) {
+            0 => MAX_RECIPIENT_VESTINGS,
+            max_vestings => max_vestings,
+        }
+    }
 
-        let recipient_vestings: Map<Address, Vec<u64>> = env
-            .storage()
-            .persistent()
-            .get(&RECIPIENT_VESTINGS)
-            .unwrap_or_else(|| Map::new(&env));
+    /// Returns true if revoking the vesting now would release more than the revoke approval threshold.
+    fn requires_revoke_approval(env: Env, vesting_id: u64) -> bool {
+        let threshold = Self::get_revoke_approval_threshold(env.clone());
 
-        let recipient_ids: Vec<u64> = recipient_vestings
-            .get(recipient)
-            .unwrap_or_else(|| Vec::new(&env));
+        threshold != 0
+            && Self::get_unvested_amount(env.clone(), vesting_id, env.ledger().timestamp())
+                > threshold
+    }
 
-        recipient_ids.len() != 0
+    /// Calculates the vested amount of a vesting at the given timestamp, taking its cliff ramp into account.
+    fn vested_amount(
+        env: Env,
+        vesting_id: u64,
+        vesting: Vesting,
+        reference_timestamp: u64,
+    ) -> i128 {
+        Self::calculate_ramped_vested_amount(
+            env.clone(),
+            vesting,
+            reference_timestamp,
+            Self::get_cliff_ramp(env, vesting_id),
+        )
     }
 
-    /// Returns the address of the token used in the vesting contract.
-    pub fn get_token_address(env: Env) -> Address {
-        Self::extend_instance_ttl(&env);
+    /// Returns the amount a recipient could claim from a vesting at the given timestamp,
+    /// which is 0 while the vesting is timelocked.
+    fn claimable_amount(
+        env: Env,
+        vesting_id: u64,
+        vesting: Vesting,
+        reference_timestamp: u64,
+    ) -> i128 {
+        if vesting.timelock > reference_timestamp {
+            return 0;
+        }
 
-        env.storage().instance().get(&TOKEN_ADDRESS).unwrap()
+        Self::vested_amount(env, vesting_id, vesting.clone(), reference_timestamp)
+            - vesting.claimed_amount
     }
 
-    /// Returns the amount of token reserved for vesting in the contract.
-    pub fn get_tokens_reserved_for_vesting(env: Env) -> i128 {
-        Self::extend_instance_ttl(&env);
+    /// Adds an amount to the running total claimed by a recipient.
+    fn add_recipient_claimed(env: &Env, recipient: Address, amount: i128) {
+        let mut recipient_claimed: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&RECIPIENT_CLAIMED)
+            .unwrap_or_else(|| Map::new(env));
 
+        let claimed_total = recipient_claimed.get(recipient.clone()).unwrap_or(0) + amount;
+        recipient_claimed.set(recipient, claimed_total);
         env.storage()
-            .instance()
-            .get(&TOKENS_RESERVED_FOR_VESTING)
-            .unwrap_or(0)
+            .persistent()
+            .set(&RECIPIENT_CLAIMED, &recipient_claimed);
+        Self::extend_persistent_ttl(env, RECIPIENT_CLAIMED);
     }
 
-    /// Internal version of `create_vesting`, used for `create_vesting_batch`.
-    /// Same but without authentication, required to make `create_vesting_batch` work properly.
-    ///
-    /// Creates a vesting schedule for a recipient and returns a vesting ID.
-    fn create_vesting_internal(
-        env: Env,
-        caller: Address,
-        recipient: Address,
-        start_timestamp: u64,
-        end_timestamp: u64,
-        timelock: u64,
-        initial_unlock: i128,
-        cliff_release_timestamp: u64,
-        cliff_amount: i128,
-        release_interval_secs: u64,
-        linear_vest_amount: i128,
-    ) -> u64 {
-        Self::extend_instance_ttl(&env);
-        Self::extend_persistent_ttl(&env, RECIPIENTS);
-        Self::extend_persistent_ttl(&env, RECIPIENT_VESTINGS);
-        Self::extend_persistent_ttl(&env, VESTING_BY_ID);
+    /// Calls the claim hook of a recipient, if any. A failing hook reverts the whole claim.
+    fn call_claim_hook(env: &Env, recipient: Address, amount: i128) {
+        if let Some(hook) = Self::get_claim_hook(env.clone(), recipient.clone()) {
+            env.invoke_contract::<()>(
+                &hook,
+                &Symbol::new(env, "on_claim"),
+                vec![env, recipient.into_val(env), amount.into_val(env)],
+            );
+        }
+    }
 
-        assert!(
-            initial_unlock >= 0 && cliff_amount >= 0 && linear_vest_amount >= 0,
-            "Invalid amount"
+    /// Marks an amount of a vesting as claimed, transfers it to the recipient and calls their
+    /// claim hook.
+    fn pay_out(env: Env, vesting_id: u64, mut vesting: Vesting, amount: i128) {
+        vesting.claimed_amount += amount;
+        Self::add_recipient_claimed(&env, vesting.recipient.clone(), amount);
+
+        Self::store_vesting(&env, vesting_id, &vesting);
+
+        let reserved_tokens = Self::release_reserved(&env, amount);
+
+        env.events().publish(
+            (CLAIMED, vesting.recipient.clone(), vesting_id),
+            (
+                vesting_id,
+                vesting.recipient.clone(),
+                amount,
+                reserved_tokens,
+            ),
+        );
+
+        Self::transfer_claimed(
+            &env,
+            Self::payout_destination(env.clone(), vesting.recipient.clone()),
+            amount,
         );
+
+        Self::call_claim_hook(&env, vesting.recipient, amount);
+    }
+
+    /// Sets the delay stored under `key`. Increases apply immediately. A decrease is first
+    /// recorded under `pending_key`, ready once the current delay has elapsed, and applies when
+    /// requested again after that.
+    fn set_delay(env: &Env, key: Symbol, pending_key: Symbol, delay_secs: u64) {
+        let current_delay_secs: u64 = env.storage().instance().get(&key).unwrap_or(0);
+
+        if delay_secs < current_delay_secs {
+            let pending_delay: Option<(u64, u64)> = env.storage().instance().get(&pending_key);
+            match pending_delay {
+                Some((pending_delay_secs, ready_timestamp)) if pending_delay_secs == delay_secs => {
+                    assert!(
+                        env.ledger().timestamp() >= ready_timestamp,
+                        "Delay decrease not ready"
+                    );
+                }
+                _ => {
+                    let ready_timestamp =
+                        env.ledger().timestamp().saturating_add(current_delay_secs);
+                    env.storage()
+                        .instance()
+                        .set(&pending_key, &(delay_secs, ready_timestamp));
+                    env.events()
+                        .publish((CONFIG_SET,), (pending_key, delay_secs));
+                    return;
+                }
+            }
+        }
+
+        env.storage().instance().remove(&pending_key);
+        Self::set_config(env, key, delay_secs);
+    }
+
+    /// Stores a configuration value and publishes its change.
+    fn set_config<V>(env: &Env, key: Symbol, value: V)
+    where
+        V: IntoVal<Env, Val>,
+        Val: TryFromVal<Env, V>,
+    {
+        env.storage().instance().set(&key, &value);
+
+        env.events().publish((CONFIG_SET,), (key, value));
+    }
+
+    /// Replaces the whole admin set, on behalf of the given acting admin.
+    fn apply_admin_set(env: &Env, actor: Address, new_admins: Vec<Address>) {
+        let admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(env));
+
         assert!(
-            linear_vest_amount + cliff_amount != 0,
-            "Invalid vested amount"
+            !new_admins.is_empty(),
+            "There must always be at least 1 admin"
         );
+
+        let mut replaced_admins: Map<Address, bool> = Map::new(env);
+        for admin in new_admins.iter() {
+            replaced_admins.set(admin, true);
+        }
+
         assert!(
-            start_timestamp != 0 && start_timestamp < end_timestamp,
-            "Invalid start timestamp"
+            replaced_admins.len() >= Self::get_action_threshold(env.clone()),
+            "Admin count below threshold"
         );
-        assert!(release_interval_secs != 0, "Invalid release interval");
 
-        if cliff_release_timestamp == 0 {
-            assert!(cliff_amount == 0, "invalid cliff amount");
-            assert!(
-                (end_timestamp - start_timestamp) % release_interval_secs == 0,
-                "Invalid interval length"
-            );
+        for (admin, is_enabled) in admins.iter() {
+            if is_enabled && !replaced_admins.contains_key(admin.clone()) {
+                Self::record_admin_change(env, actor.clone(), admin.clone(), false);
+                env.events().publish((ADMIN_ACCESS_SET,), (admin, false));
+            }
+        }
+
+        for admin in replaced_admins.keys().iter() {
+            if !admins.get(admin.clone()).unwrap_or(false) {
+                assert!(
+                    !Self::is_admin_recipients_blocked(env.clone())
+                        || !Self::is_recipient(env.clone(), admin.clone()),
+                    "Admin is a recipient"
+                );
+                Self::record_admin_change(env, actor.clone(), admin.clone(), true);
+                env.events().publish((ADMIN_ACCESS_SET,), (admin, true));
+            }
+        }
+
+        env.storage().persistent().set(&ADMINS, &replaced_admins);
+        env.storage()
+            .instance()
+            .set(&ADMIN_COUNT, &replaced_admins.len());
+    }
+
+    /// Marks a vesting as irrevocable.
+    fn mark_irrevocable(env: &Env, vesting_id: u64) {
+        Self::set_vesting_flag(env, IRREVOCABLE_VESTINGS, vesting_id, true);
+    }
+
+    /// Returns the flag of a vesting in the per-vesting map stored under `key`, false if unset.
+    fn vesting_flag(env: &Env, key: Symbol, vesting_id: u64) -> bool {
+        let flags: Map<u64, bool> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(env));
+
+        flags.get(vesting_id).unwrap_or(false)
+    }
+
+    /// Sets the flag of a vesting in the per-vesting map stored under `key`, removing it when false.
+    fn set_vesting_flag(env: &Env, key: Symbol, vesting_id: u64, flag: bool) {
+        let mut flags: Map<u64, bool> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(env));
+
+        if flag {
+            flags.set(vesting_id, true);
         } else {
-            assert!(cliff_amount != 0, "Invalid cliff amount");
-            assert!(
-                start_timestamp <= cliff_release_timestamp
-                    && cliff_release_timestamp < end_timestamp,
-                "Invalid cliff release"
-            );
-            assert!(
-                (end_timestamp - cliff_release_timestamp) % release_interval_secs == 0,
-                "Invalid interval length"
-            );
+            flags.remove(vesting_id);
         }
+        env.storage().persistent().set(&key, &flags);
+        Self::extend_persistent_ttl(env, key);
+    }
 
-        let total_expected_amount = initial_unlock + cliff_amount + linear_vest_amount;
+    /// Copies the entry of a vesting in the per-vesting map stored under `key` to another vesting.
+    fn copy_vesting_entry(env: &Env, key: Symbol, from_vesting_id: u64, to_vesting_id: u64) {
+        let mut entries: Map<u64, Val> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(env));
 
-        let reserved_tokens = env
+        if let Some(entry) = entries.get(from_vesting_id) {
+            entries.set(to_vesting_id, entry);
+            env.storage().persistent().set(&key, &entries);
+            Self::extend_persistent_ttl(env, key);
+        }
+    }
+
+    /// Removes the entry of a vesting, if any, from the per-vesting map stored under `key`.
+    fn remove_vesting_entry(env: &Env, key: Symbol, vesting_id: u64) {
+        let mut entries: Map<u64, Val> = env
             .storage()
-            .instance()
-            .get(&TOKENS_RESERVED_FOR_VESTING)
-            .unwrap_or(0_i128)
-            + total_expected_amount;
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(env));
+
+        if entries.contains_key(vesting_id) {
+            entries.remove(vesting_id);
+            env.storage().persistent().set(&key, &entries);
+        }
+    }
+
+    /// Appends an admin change to the admin history, dropping the oldest beyond the cap.
+    fn record_admin_change(env: &Env, actor: Address, target: Address, is_enabled: bool) {
+        let mut admin_history: Vec<(u64, Address, Address, bool)> = env
+            .storage()
+            .persistent()
+            .get(&ADMIN_HISTORY)
+            .unwrap_or_else(|| Vec::new(env));
+
+        admin_history.push_back((env.ledger().timestamp(), actor, target, is_enabled));
+        while admin_history.len() > ADMIN_HISTORY_CAP {
+            admin_history.pop_front();
+        }
 
         env.storage()
-            .instance()
-            .set(&TOKENS_RESERVED_FOR_VESTING, &reserved_tokens);
+            .persistent()
+            .set(&ADMIN_HISTORY, &admin_history);
+        Self::extend_persistent_ttl(env, ADMIN_HISTORY);
+    }
 
-        let vesting: Vesting = Vesting {
-            recipient: recipient.clone(),
-            start_timestamp,
-            end_timestamp,
-            deactivation_timestamp: 0,
-            timelock,
-            release_interval_secs,
-            cliff_release_timestamp,
-            initial_unlock,
-            cliff_amount,
-            linear_vest_amount,
-            claimed_amount: 0,
-        };
+    /// Returns true if the vesting can be claimed with respect to the acknowledgement requirement.
+    fn acknowledgement_satisfied(env: Env, vesting_id: u64) -> bool {
+        !Self::is_ack_required(env.clone()) || Self::is_acknowledged(env, vesting_id)
+    }
 
-        let vesting_id: u64 = env.storage().instance().get(&NONCE).unwrap_or(0);
-        let new_vesting_id: u64 = vesting_id + 1;
-        env.storage().instance().set(&NONCE, &new_vesting_id);
+    /// Returns true if the claim cooldown of a vesting has elapsed. The final claim draining the
+    /// vesting is always allowed regardless of the cooldown.
+    fn claim_cooldown_elapsed(
+        env: Env,
+        vesting_id: u64,
+        vesting: Vesting,
+        claimable: i128,
+    ) -> bool {
+        let cooldown_secs: u64 = Self::get_claim_cooldown(env.clone());
+        if cooldown_secs == 0 {
+            return true;
+        }
 
-        if !Self::is_recipient(env.clone(), recipient.clone()) {
-            let mut recipients: Vec<Address> = env
-                .storage()
-                .persistent()
-                .get(&RECIPIENTS)
-                .unwrap_or_else(|| Vec::new(&env));
+        let final_vest_amount = Self::vested_amount(
+            env.clone(),
+            vesting_id,
+            vesting.clone(),
+            vesting.end_timestamp,
+        );
+        if vesting.claimed_amount + claimable == final_vest_amount {
+            return true;
+        }
 
-            recipients.push_back(recipient.clone());
-            env.storage().persistent().set(&RECIPIENTS, &recipients);
+        let last_claim_timestamps: Map<u64, u64> = env
+            .storage()
+            .persistent()
+            .get(&LAST_CLAIM_TIMESTAMPS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        match last_claim_timestamps.get(vesting_id) {
+            Some(last_claim_timestamp) => {
+                env.ledger().timestamp() - last_claim_timestamp >= cooldown_secs
+            }
+            None => true,
         }
+    }
 
-        let mut vesting_by_id: Map<u64, Vesting> = env
+    /// Records the timestamp of a claim for a vesting, only while a claim cooldown is set.
+    fn record_claim_timestamp(env: Env, vesting_id: u64) {
+        if Self::get_claim_cooldown(env.clone()) == 0 {
+            return;
+        }
+
+        let mut last_claim_timestamps: Map<u64, u64> = env
             .storage()
             .persistent()
-            .get(&VESTING_BY_ID)
+            .get(&LAST_CLAIM_TIMESTAMPS)
             .unwrap_or_else(|| Map::new(&env));
 
-        vesting_by_id.set(vesting_id, vesting.clone());
+        last_claim_timestamps.set(vesting_id, env.ledger().timestamp());
         env.storage()
             .persistent()
-            .set(&VESTING_BY_ID, &vesting_by_id);
+            .set(&LAST_CLAIM_TIMESTAMPS, &last_claim_timestamps);
+        Self::extend_persistent_ttl(&env, LAST_CLAIM_TIMESTAMPS);
+    }
 
-        let mut recipient_vestings: Map<Address, Vec<u64>> = env
+    /// Approval check for sensitive admin actions.
+    /// Panics if the action has fewer valid approvals than the threshold, otherwise clears them.
+    fn consume_action_approvals(env: Env, action_hash: BytesN<32>) {
+        if Self::get_action_threshold(env.clone()) <= 1 {
+            return;
+        }
+
+        assert!(
+            Self::get_action_approvals(env.clone(), action_hash.clone())
+                >= Self::get_action_threshold(env.clone()),
+            "Insufficient approvals"
+        );
+
+        let mut action_approvals: Map<BytesN<32>, Map<Address, u64>> = env
             .storage()
             .persistent()
-            .get(&RECIPIENT_VESTINGS)
+            .get(&ACTION_APPROVALS)
             .unwrap_or_else(|| Map::new(&env));
 
-        let mut recipient_ids: Vec<u64> = recipient_vestings
-            .get(recipient.clone())
-            .unwrap_or_else(|| Vec::new(&env));
-        recipient_ids.push_back(vesting_id);
-        recipient_vestings.set(recipient.clone(), recipient_ids);
-
+        action_approvals.remove(action_hash);
         env.storage()
             .persistent()
-            .set(&RECIPIENT_VESTINGS, &recipient_vestings);
-
-        env.events()
-            .publish((VESTING_CREATED,), (vesting_id.clone(), recipient, vesting));
+            .set(&ACTION_APPROVALS, &action_approvals);
+    }
 
-        let token_address: Address = env.storage().instance().get(&TOKEN_ADDRESS).unwrap();
+    /// Adds a new admin or removes an existing one, on behalf of the given acting admin.
+    fn apply_admin_change(env: &Env, actor: Address, admin: Address, is_enabled: bool) {
+        let mut admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(env));
 
-        TokenClient::new(&env, &token_address).transfer_from(
-            &env.current_contract_address(),
-            &caller,
-            &env.current_contract_address(),
-            &total_expected_amount,
+        assert!(
+            admins.get(admin.clone()).unwrap_or(false) != is_enabled,
+            "Flag provided already set"
         );
 
-        vesting_id
+        let admin_count: u32 = env.storage().instance().get(&ADMIN_COUNT).unwrap_or(0);
+
+        if is_enabled {
+            assert!(
+                !Self::is_admin_recipients_blocked(env.clone())
+                    || !Self::is_recipient(env.clone(), admin.clone()),
+                "Admin is a recipient"
+            );
+            let new_admin_count: u32 = admin_count + 1;
+            env.storage().instance().set(&ADMIN_COUNT, &new_admin_count);
+        } else {
+            assert!(admin_count > 1, "There must always be at least 1 admin");
+            assert!(
+                admin_count > Self::get_action_threshold(env.clone()),
+                "Admin count below threshold"
+            );
+            let new_admin_count: u32 = admin_count - 1;
+            env.storage().instance().set(&ADMIN_COUNT, &new_admin_count);
+        }
+
+        admins.set(admin.clone(), is_enabled);
+        env.storage().persistent().set(&ADMINS, &admins);
+        Self::record_admin_change(env, actor, admin.clone(), is_enabled);
+        env.events()
+            .publish((ADMIN_ACCESS_SET,), (admin, is_enabled));
     }
 
     /// Access control check for admin functions.
+    /// Extends the contract TTLs and checks that `caller` is an admin and authorized the call.
+    fn require_admin(env: &Env, caller: &Address) {
+        Self::extend_instance_ttl(env);
+        Self::extend_persistent_ttl(env, ADMINS);
+
+        let admins: Map<Address, bool> = env
+            .storage()
+            .persistent()
+            .get(&ADMINS)
+            .unwrap_or_else(|| Map::new(env));
+        Self::admin_check(caller.clone(), admins);
+    }
+
     fn admin_check(caller: Address, admins: Map<Address, bool>) {
         caller.require_auth();
         if !admins.get(caller.clone()).unwrap_or(false) {
             panic!("Not an admin");
         }
+
+        // Any admin action resets the recovery inactivity window.
+        let env = caller.env();
+        env.storage()
+            .instance()
+            .set(&LAST_ADMIN_ACTION, &env.ledger().timestamp());
     }
 }
 
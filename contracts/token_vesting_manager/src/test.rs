@@ -2,23 +2,105 @@
 
 use super::*;
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{testutils::Address as TestAddress, testutils::Ledger, vec, Env};
+use soroban_sdk::{
+    testutils::Address as TestAddress, testutils::Events, testutils::Ledger, vec, Env, IntoVal,
+    TryFromVal,
+};
+
+/// Claim hook recording the last callback it received, or rejecting callbacks when failing.
+#[contract]
+pub struct MockClaimHook;
+
+#[contractimpl]
+impl MockClaimHook {
+    pub fn set_failing(env: Env, failing: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("FAILING"), &failing);
+    }
+
+    pub fn on_claim(env: Env, recipient: Address, amount: i128) {
+        let failing: bool = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("FAILING"))
+            .unwrap_or(false);
+        assert!(!failing, "Hook failed");
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("LASTCALL"), &(recipient, amount));
+    }
+
+    pub fn last_call(env: Env) -> Option<(Address, i128)> {
+        env.storage().instance().get(&symbol_short!("LASTCALL"))
+    }
+}
+
+/// Token under-delivering transfers by a configurable fee, standing for a token with transfer
+/// hooks or restrictions. `transfer_from` is unaffected so vestings can be funded.
+#[contract]
+pub struct MockFeeToken;
+
+#[contractimpl]
+impl MockFeeToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        Self::set_balance(&env, to, balance + amount);
+    }
+
+    pub fn set_fee(env: Env, fee: i128) {
+        env.storage().instance().set(&symbol_short!("FEE"), &fee);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+        i128::MAX
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let fee: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("FEE"))
+            .unwrap_or(0);
+        Self::move_balance(&env, from, to, amount - fee);
+    }
+
+    pub fn transfer_from(env: Env, _spender: Address, from: Address, to: Address, amount: i128) {
+        Self::move_balance(&env, from, to, amount);
+    }
+
+    fn move_balance(env: &Env, from: Address, to: Address, amount: i128) {
+        let from_balance = Self::balance(env.clone(), from.clone());
+        Self::set_balance(env, from, from_balance - amount);
+        let to_balance = Self::balance(env.clone(), to.clone());
+        Self::set_balance(env, to, to_balance + amount);
+    }
+
+    fn set_balance(env: &Env, id: Address, amount: i128) {
+        env.storage().instance().set(&id, &amount);
+    }
+}
 
 fn deploy_manager_helper(
     env: &Env,
 ) -> (
-    TokenVestingManagerClient,
+    TokenVestingManagerClient<'_>,
     Address,
-    TokenClient,
-    StellarAssetClient,
+    TokenClient<'_>,
+    StellarAssetClient<'_>,
     Address,
 ) {
     let contract_id = env.register(TokenVestingManager, ());
     let client = TokenVestingManagerClient::new(env, &contract_id);
 
     let admin: Address = Address::generate(env);
-    let (token_client, token_admin_client, token_address) = deploy_token_helper(&env);
-    client.init(&admin, &token_address);
+    let (token_client, token_admin_client, token_address) = deploy_token_helper(env);
+    client.init(&admin, &token_address, &None, &0);
 
     (
         client,
@@ -29,12 +111,12 @@ fn deploy_manager_helper(
     )
 }
 
-fn deploy_token_helper(env: &Env) -> (TokenClient, StellarAssetClient, Address) {
-    let token_admin = Address::generate(&env);
+fn deploy_token_helper(env: &Env) -> (TokenClient<'_>, StellarAssetClient<'_>, Address) {
+    let token_admin = Address::generate(env);
     let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_admin_client: StellarAssetClient<'_> =
-        StellarAssetClient::new(&env, &token_contract_id.address());
-    let token_client = TokenClient::new(&env, &token_contract_id.address());
+        StellarAssetClient::new(env, &token_contract_id.address());
+    let token_client = TokenClient::new(env, &token_contract_id.address());
 
     (
         token_client,
@@ -50,7 +132,7 @@ fn test_double_initialization() {
     let (client, admin, _, _, token_address) = deploy_manager_helper(&env);
 
     // Panics given that init can only be called once.
-    client.init(&admin, &token_address);
+    client.init(&admin, &token_address, &None, &0);
 }
 
 #[test]
@@ -2118,7 +2200,7 @@ fn test_is_recipient() {
         &linear_vest_amount,
     );
 
-    assert_eq!(client.is_recipient(&recipient), true);
+    assert!(client.is_recipient(&recipient));
 }
 
 #[test]
@@ -2186,3 +2268,5781 @@ fn test_get_token_address() {
 
     assert_eq!(client.get_token_address(), token_address);
 }
+
+#[test]
+fn test_withdraw_admin_with_threshold() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&client.address, &amount);
+
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+    assert_eq!(client.get_action_threshold(), 2);
+    assert_eq!(client.get_approval_window(), 100);
+
+    let action_hash = withdraw_action_hash(&env, admin.clone(), amount);
+    client.propose_action(&admin, &action_hash);
+    assert_eq!(client.get_action_approvals(&action_hash), 1);
+    client.propose_action(&second_admin, &action_hash);
+    assert_eq!(client.get_action_approvals(&action_hash), 2);
+
+    client.withdraw_admin(&admin, &amount);
+    assert_eq!(token_client.balance(&admin), 1000);
+
+    // Approvals are consumed by the withdrawal.
+    assert_eq!(client.get_action_approvals(&action_hash), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_withdraw_admin_with_threshold_single_approval() {
+    let env = Env::default();
+    let (client, admin, _, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&client.address, &amount);
+
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+
+    let action_hash = withdraw_action_hash(&env, admin.clone(), amount);
+    client.propose_action(&admin, &action_hash);
+
+    // Panics because only one admin approved the withdrawal.
+    client.withdraw_admin(&admin, &amount);
+}
+
+#[test]
+#[should_panic]
+fn test_withdraw_admin_with_threshold_expired_approval() {
+    let env = Env::default();
+    let (client, admin, _, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&client.address, &amount);
+
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+
+    let action_hash = withdraw_action_hash(&env, admin.clone(), amount);
+    client.propose_action(&admin, &action_hash);
+    env.ledger().set_timestamp(200);
+    client.propose_action(&second_admin, &action_hash);
+
+    // Panics because the first approval is outside of the approval window.
+    client.withdraw_admin(&admin, &amount);
+}
+
+#[test]
+#[should_panic]
+fn test_set_action_threshold_above_admin_count() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    env.mock_all_auths();
+
+    // Panics because there is a single admin.
+    client.set_action_threshold(&admin, &2, &100);
+}
+
+#[test]
+fn test_admin_changes_with_threshold() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let second_admin: Address = Address::generate(&env);
+    let third_admin: Address = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+
+    // A single admin can no longer change the admin set.
+    assert!(client.try_set_admin(&admin, &third_admin, &true).is_err());
+    let action_hash = set_admin_action_hash(&env, admin.clone(), third_admin.clone(), true);
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&second_admin, &action_hash);
+    client.set_admin(&admin, &third_admin, &true);
+    assert!(client.is_admin(&third_admin));
+    assert_eq!(client.get_action_approvals(&action_hash), 0);
+
+    let new_admins = vec![&env, admin.clone(), second_admin.clone()];
+    assert!(client.try_replace_admins(&admin, &new_admins).is_err());
+    let action_hash = replace_admins_action_hash(&env, admin.clone(), new_admins.clone());
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&third_admin, &action_hash);
+    client.replace_admins(&admin, &new_admins);
+    assert!(!client.is_admin(&third_admin));
+
+    // Nor lower the threshold.
+    assert!(client.try_set_action_threshold(&admin, &1, &0).is_err());
+    let action_hash = threshold_action_hash(&env, admin.clone(), 1, 0);
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&second_admin, &action_hash);
+    client.set_action_threshold(&admin, &1, &0);
+    assert_eq!(client.get_action_threshold(), 1);
+}
+
+#[test]
+fn test_claim_all() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = 4000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    // Claimable vesting.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &1000,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    // Timelocked vesting.
+    let timelocked_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &5000,
+        &0,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    // Vesting not started yet.
+    let not_started_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &3000,
+        &4000,
+        &0,
+        &0,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(1500);
+
+    assert_eq!(client.claim_all(&recipient), 1500);
+
+    let mut skipped: Vec<(u32, u64, u32)> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == client.address && topics == vec![&env, BATCH_SKIPPED.into_val(&env)]
+        {
+            skipped.push_back(<(u32, u64, u32)>::try_from_val(&env, &data).unwrap());
+        }
+    }
+
+    assert_eq!(
+        skipped,
+        vec![
+            &env,
+            (1, timelocked_vesting_id, SKIP_TIMELOCKED),
+            (2, not_started_vesting_id, SKIP_NOTHING_TO_CLAIM),
+        ]
+    );
+
+    assert_eq!(token_client.balance(&recipient), 1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 2500);
+}
+
+#[test]
+fn test_claim_all_nothing_to_claim() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Nothing has vested yet, so the call is a no-op.
+    assert_eq!(client.claim_all(&recipient), 0);
+    assert_eq!(env.events().all().len(), 0);
+
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount
+    );
+    assert_eq!(client.claim_all(&Address::generate(&env)), 0);
+}
+
+#[test]
+fn test_reclaim_abandoned() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_reclaim_grace_secs(&admin, &500);
+    assert_eq!(client.get_reclaim_grace_secs(), 500);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+
+    env.ledger().set_timestamp(end_timestamp + 501);
+    client.reclaim_abandoned(&admin, &vesting_id);
+
+    let mut drained_events: Vec<(u64, Address, i128)> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == client.address
+            && topics
+                == vec![
+                    &env,
+                    VESTING_DRAINED.into_val(&env),
+                    recipient.into_val(&env),
+                    vesting_id.into_val(&env),
+                ]
+        {
+            drained_events.push_back(<(u64, Address, i128)>::try_from_val(&env, &data).unwrap());
+        }
+    }
+    assert_eq!(
+        drained_events,
+        vec![&env, (vesting_id, recipient.clone(), 500)]
+    );
+
+    // The unclaimed tokens are released to the admin withdrawable balance, not transferred.
+    assert_eq!(token_client.balance(&admin), 0);
+    assert_eq!(token_client.balance(&recipient), 1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 500);
+    assert_eq!(
+        client.get_vesting_info(&vesting_id).claimed_amount,
+        total_expected_amount
+    );
+    // Only the tokens paid to the recipient count towards their claimed total.
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 1500);
+
+    // Withdrawing them goes through the admin withdrawal flow.
+    client.withdraw_admin(&admin, &500);
+    assert_eq!(token_client.balance(&admin), 500);
+}
+
+#[test]
+#[should_panic]
+fn test_reclaim_abandoned_irrevocable() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_reclaim_grace_secs(&admin, &500);
+
+    env.ledger().set_timestamp(end_timestamp + 501);
+
+    // Panics because irrevocable vestings cannot be reclaimed.
+    client.reclaim_abandoned(&admin, &vesting_id);
+}
+
+#[test]
+#[should_panic]
+fn test_reclaim_abandoned_before_grace() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_reclaim_grace_secs(&admin, &500);
+
+    env.ledger().set_timestamp(end_timestamp + 500);
+
+    // Panics because the grace period has not elapsed yet.
+    client.reclaim_abandoned(&admin, &vesting_id);
+}
+
+#[test]
+#[should_panic]
+fn test_reclaim_abandoned_without_grace() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(end_timestamp + 100_000);
+
+    // Panics because no grace period has been set.
+    client.reclaim_abandoned(&admin, &vesting_id);
+}
+
+#[test]
+#[should_panic]
+fn test_create_vesting_should_panic_if_amount_overflow() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = i128::MAX;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1;
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    // Panics because the total amount of the vesting overflows.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_create_vesting_should_panic_if_reserved_overflow() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = i128::MAX;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), i128::MAX);
+
+    // Panics because the total reserved amount overflows.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &1,
+    );
+}
+
+#[test]
+fn test_init_returns_contract_address() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = TokenVestingManagerClient::new(&env, &contract_id);
+
+    let admin: Address = Address::generate(&env);
+    let (_, _, token_address) = deploy_token_helper(&env);
+
+    assert_eq!(client.init(&admin, &token_address, &None, &0), contract_id);
+}
+
+#[test]
+fn test_init_with_recovery() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = TokenVestingManagerClient::new(&env, &contract_id);
+
+    let admin: Address = Address::generate(&env);
+    let recovery: Address = Address::generate(&env);
+    let recovery_delay_secs: u64 = 2_592_000;
+    let (_, _, token_address) = deploy_token_helper(&env);
+
+    // The inactivity delay must be long.
+    assert!(client
+        .try_init(&admin, &token_address, &Some(recovery.clone()), &86400)
+        .is_err());
+
+    client.init(
+        &admin,
+        &token_address,
+        &Some(recovery.clone()),
+        &recovery_delay_secs,
+    );
+    assert_eq!(client.get_recovery_address(), Some(recovery));
+    assert_eq!(client.get_recovery_delay(), recovery_delay_secs);
+}
+
+#[test]
+fn test_init_event() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = TokenVestingManagerClient::new(&env, &contract_id);
+
+    let admin: Address = Address::generate(&env);
+    let (_, _, token_address) = deploy_token_helper(&env);
+
+    client.init(&admin, &token_address, &None, &0);
+
+    let mut init_events: Vec<(Address, Vec<Address>)> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == contract_id
+            && topics == vec![&env, symbol_short!("INIT").into_val(&env)]
+        {
+            init_events.push_back(<(Address, Vec<Address>)>::try_from_val(&env, &data).unwrap());
+        }
+    }
+
+    assert_eq!(init_events, vec![&env, (token_address, vec![&env, admin])]);
+}
+
+#[test]
+fn test_claim_after_cooldown() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_claim_cooldown(&admin, &100);
+    assert_eq!(client.get_claim_cooldown(), 100);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 1500);
+
+    env.ledger().set_timestamp(start_timestamp + 600);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 1600);
+
+    // The final claim draining the vesting is allowed regardless of the cooldown.
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 2000);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_before_cooldown() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_claim_cooldown(&admin, &100);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+
+    env.ledger().set_timestamp(start_timestamp + 550);
+
+    // Panics because the claim cooldown is still active.
+    client.claim(&recipient, &vesting_id);
+}
+
+#[test]
+fn test_replace_admins() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    env.mock_all_auths();
+    let kept_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &kept_admin, &true);
+
+    let new_admin: Address = Address::generate(&env);
+    client.replace_admins(
+        &admin,
+        &vec![
+            &env,
+            kept_admin.clone(),
+            new_admin.clone(),
+            new_admin.clone(),
+        ],
+    );
+
+    let mut admin_changes: Vec<(Address, bool)> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == client.address
+            && topics == vec![&env, ADMIN_ACCESS_SET.into_val(&env)]
+        {
+            admin_changes.push_back(<(Address, bool)>::try_from_val(&env, &data).unwrap());
+        }
+    }
+    assert_eq!(
+        admin_changes,
+        vec![&env, (admin.clone(), false), (new_admin.clone(), true)]
+    );
+
+    assert!(!client.is_admin(&admin));
+    assert!(client.is_admin(&kept_admin));
+    assert!(client.is_admin(&new_admin));
+    assert_eq!(client.get_admins_count(), 2);
+}
+
+#[test]
+#[should_panic]
+fn test_replace_admins_with_empty_set() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    env.mock_all_auths();
+
+    // Panics because there must always be at least 1 admin.
+    client.replace_admins(&admin, &Vec::new(&env));
+}
+
+#[test]
+fn test_set_vesting_paused() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = 2 * (initial_unlock + cliff_amount + linear_vest_amount);
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let paused_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_vesting_paused(&admin, &paused_vesting_id, &true);
+    assert!(client.is_vesting_paused(&paused_vesting_id));
+    assert!(!client.is_vesting_paused(&vesting_id));
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+
+    assert!(client.try_claim(&recipient, &paused_vesting_id).is_err());
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 1500);
+
+    client.set_vesting_paused(&admin, &paused_vesting_id, &false);
+    assert!(!client.is_vesting_paused(&paused_vesting_id));
+
+    client.claim(&recipient, &paused_vesting_id);
+    assert_eq!(token_client.balance(&recipient), 3000);
+}
+
+#[test]
+fn test_create_vesting_single_release_interval() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 1000;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    assert!(!client.is_single_interval_allowed());
+    client.set_single_interval_allowed(&admin, &true);
+    assert!(client.is_single_interval_allowed());
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Nothing is released before the single interval has fully elapsed.
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(end_timestamp - 1)),
+        0
+    );
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &end_timestamp),
+        linear_vest_amount
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), linear_vest_amount);
+}
+
+#[test]
+#[should_panic]
+fn test_create_vesting_single_release_interval_not_allowed() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 500;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 500;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    // Panics because the release interval spans the whole linear duration after the cliff.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+fn test_deposit() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 500;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &amount);
+    token_client.approve(&admin, &client.address, &amount, &expiration_ledger);
+
+    assert_eq!(client.amount_to_withdraw_by_admin(), 0);
+
+    client.deposit(&admin, &amount);
+
+    let deposited_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("DEPOSITED").into_val(&env)]
+        })
+        .count();
+    assert_eq!(deposited_events, 1);
+
+    assert_eq!(token_client.balance(&admin), 0);
+    assert_eq!(token_client.balance(&client.address), amount);
+    assert_eq!(client.amount_to_withdraw_by_admin(), amount);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient allowance")]
+fn test_create_vesting_insufficient_allowance() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount - 1),
+        &expiration_ledger,
+    );
+
+    // Panics because the approved amount does not cover the vesting.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+fn test_purge_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let drained_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let active_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &drained_vesting_id);
+
+    let reserved_before = client.get_tokens_reserved_for_vesting();
+
+    client.purge_vesting(&admin, &drained_vesting_id);
+
+    assert!(client.try_get_vesting_info(&drained_vesting_id).is_err());
+    assert_eq!(
+        client.get_all_recipient_vestings(&recipient),
+        vec![&env, active_vesting_id]
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), reserved_before);
+}
+
+#[test]
+fn test_purge_last_vesting_and_recreate() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+
+    // Purging the last vesting of the recipient removes them from the recipients.
+    client.purge_vesting(&admin, &vesting_id);
+
+    assert!(!client.is_recipient(&recipient));
+    assert_eq!(client.get_all_recipients_len(), 0);
+    assert_eq!(client.get_all_recipient_vestings_len(&recipient), 0);
+
+    // A new vesting for the same recipient lists them only once.
+    let new_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &(end_timestamp + 1000),
+        &(end_timestamp + 2000),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(client.get_all_recipients_len(), 1);
+    assert_eq!(client.get_all_recipients(), vec![&env, recipient.clone()]);
+    assert_eq!(
+        client.get_all_recipient_vestings(&recipient),
+        vec![&env, new_vesting_id]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_purge_active_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+
+    // Panics because the vesting still has tokens left to claim.
+    client.purge_vesting(&admin, &vesting_id);
+}
+
+#[test]
+fn test_cliff_ramp() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 1000;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let instant_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let ramped_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let cliff_ramp_secs: u64 = 100;
+    client.set_cliff_ramp(&admin, &ramped_vesting_id, &cliff_ramp_secs);
+    assert_eq!(client.get_cliff_ramp(&ramped_vesting_id), cliff_ramp_secs);
+    assert_eq!(client.get_cliff_ramp(&instant_vesting_id), 0);
+
+    // Midpoint of the ramp: 5 linear intervals of 10 tokens have also been released.
+    let midpoint: u64 = cliff_release_timestamp + cliff_ramp_secs / 2;
+    assert_eq!(
+        client.get_claimable_amount(&instant_vesting_id, &midpoint),
+        1000 + 50
+    );
+    assert_eq!(
+        client.get_claimable_amount(&ramped_vesting_id, &midpoint),
+        500 + 50
+    );
+    let ramped_vesting = client.get_vesting_info(&ramped_vesting_id);
+    assert_eq!(
+        client.calculate_ramped_vested_amount(&ramped_vesting, &midpoint, &cliff_ramp_secs),
+        500 + 50
+    );
+    assert_eq!(
+        client.calculate_vested_amount(&ramped_vesting, &midpoint),
+        1000 + 50
+    );
+
+    // Nothing is released before the cliff, and the whole cliff amount after the ramp.
+    assert_eq!(
+        client.get_claimable_amount(&ramped_vesting_id, &(cliff_release_timestamp - 1)),
+        0
+    );
+    assert_eq!(
+        client.get_claimable_amount(
+            &ramped_vesting_id,
+            &(cliff_release_timestamp + cliff_ramp_secs)
+        ),
+        1000 + 100
+    );
+
+    env.ledger().set_timestamp(midpoint);
+    client.claim(&recipient, &ramped_vesting_id);
+    assert_eq!(token_client.balance(&recipient), 500 + 50);
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &ramped_vesting_id);
+    assert_eq!(token_client.balance(&recipient), total_expected_amount);
+}
+
+#[test]
+fn test_set_cliff_ramp_restrictions() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 1000;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let claimed_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let irrevocable_vesting_id: u64 = client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // The initial unlock is claimed before the cliff.
+    env.ledger().set_timestamp(start_timestamp);
+    client.claim(&recipient, &claimed_vesting_id);
+
+    // Vestings with claims and irrevocable vestings keep their cliff.
+    assert!(client
+        .try_set_cliff_ramp(&admin, &claimed_vesting_id, &100)
+        .is_err());
+    assert!(client
+        .try_set_cliff_ramp(&admin, &irrevocable_vesting_id, &100)
+        .is_err());
+    assert_eq!(client.get_cliff_ramp(&claimed_vesting_id), 0);
+    assert_eq!(client.get_cliff_ramp(&irrevocable_vesting_id), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_set_cliff_ramp_after_cliff_release() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 1000;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(cliff_release_timestamp);
+
+    // Panics because the cliff amount has already been released.
+    client.set_cliff_ramp(&admin, &vesting_id, &100);
+}
+
+#[test]
+fn test_withdraw_after_delay() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+    let delay_secs: u64 = 3600;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &amount);
+    token_client.approve(&admin, &client.address, &amount, &expiration_ledger);
+    client.deposit(&admin, &amount);
+
+    client.set_withdraw_delay(&admin, &delay_secs);
+    assert_eq!(client.get_withdraw_delay(), delay_secs);
+
+    // Direct withdrawals are disabled while a delay is set.
+    assert!(client.try_withdraw_admin(&admin, &amount).is_err());
+
+    env.ledger().set_timestamp(1000);
+    client.request_withdraw(&admin, &amount);
+
+    let pending_withdrawal = client.get_pending_withdrawal();
+    assert_eq!(pending_withdrawal.requester, admin);
+    assert_eq!(pending_withdrawal.amount, amount);
+    assert_eq!(pending_withdrawal.ready_timestamp, 1000 + delay_secs);
+
+    env.ledger().set_timestamp(1000 + delay_secs);
+    client.execute_withdraw(&admin);
+
+    assert_eq!(token_client.balance(&admin), amount);
+    assert!(client.try_get_pending_withdrawal().is_err());
+
+    // Shortening the delay only applies once the current delay has elapsed.
+    client.set_withdraw_delay(&admin, &0);
+    assert_eq!(client.get_withdraw_delay(), delay_secs);
+    assert_eq!(
+        client.get_pending_withdraw_delay(),
+        Some((0, 1000 + delay_secs * 2))
+    );
+    env.ledger().set_timestamp(1000 + delay_secs * 2 - 1);
+    assert!(client.try_set_withdraw_delay(&admin, &0).is_err());
+    env.ledger().set_timestamp(1000 + delay_secs * 2);
+    client.set_withdraw_delay(&admin, &0);
+    assert_eq!(client.get_withdraw_delay(), 0);
+    assert_eq!(client.get_pending_withdraw_delay(), None);
+}
+
+#[test]
+#[should_panic]
+fn test_withdraw_before_delay() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+    let delay_secs: u64 = 3600;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &amount);
+    token_client.approve(&admin, &client.address, &amount, &expiration_ledger);
+    client.deposit(&admin, &amount);
+
+    client.set_withdraw_delay(&admin, &delay_secs);
+
+    env.ledger().set_timestamp(1000);
+    client.request_withdraw(&admin, &amount);
+
+    env.ledger().set_timestamp(1000 + delay_secs - 1);
+
+    // Panics because the withdrawal delay has not elapsed yet.
+    client.execute_withdraw(&admin);
+}
+
+#[test]
+fn test_cancel_withdraw() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &amount);
+    token_client.approve(&admin, &client.address, &amount, &expiration_ledger);
+    client.deposit(&admin, &amount);
+
+    client.set_withdraw_delay(&admin, &3600);
+    client.request_withdraw(&admin, &amount);
+    client.cancel_withdraw(&admin);
+
+    assert!(client.try_get_pending_withdrawal().is_err());
+    assert!(client.try_execute_withdraw(&admin).is_err());
+    assert_eq!(token_client.balance(&client.address), amount);
+}
+
+#[test]
+fn test_get_balance() {
+    let env = Env::default();
+    let (client, _, _, token_admin_client, _) = deploy_manager_helper(&env);
+
+    assert_eq!(client.get_balance(), 0);
+
+    // Mock the token admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&client.address, &1234);
+
+    assert_eq!(client.get_balance(), 1234);
+}
+
+#[test]
+fn test_claim_after_acknowledgement() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_require_ack(&admin, &true);
+    assert!(client.is_ack_required());
+    assert!(!client.is_acknowledged(&vesting_id));
+
+    env.ledger().set_timestamp(end_timestamp);
+
+    // Claims are rejected until the recipient acknowledges the vesting.
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert_eq!(client.claim_all(&recipient), 0);
+
+    client.acknowledge(&recipient, &vesting_id);
+
+    let acknowledged_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("VESTACKED").into_val(&env)]
+        })
+        .count();
+    assert_eq!(acknowledged_events, 1);
+    assert!(client.is_acknowledged(&vesting_id));
+
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), total_expected_amount);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_before_acknowledgement() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_require_ack(&admin, &true);
+
+    env.ledger().set_timestamp(end_timestamp);
+
+    // Panics because the recipient has not acknowledged the vesting.
+    client.claim(&recipient, &vesting_id);
+}
+
+#[test]
+fn test_vested_amount_at_start_with_initial_unlock_and_cliff() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let vesting = client.get_vesting_info(&vesting_id);
+
+    assert_eq!(
+        client.calculate_vested_amount(&vesting, &(start_timestamp - 1)),
+        0
+    );
+    // Only the initial unlock is vested at the start.
+    assert_eq!(
+        client.calculate_vested_amount(&vesting, &start_timestamp),
+        initial_unlock
+    );
+    assert_eq!(
+        client.calculate_vested_amount(&vesting, &(cliff_release_timestamp - 1)),
+        initial_unlock
+    );
+    // The cliff is vested at its release timestamp, before any linear interval.
+    assert_eq!(
+        client.calculate_vested_amount(&vesting, &cliff_release_timestamp),
+        initial_unlock + cliff_amount
+    );
+    assert_eq!(
+        client
+            .calculate_vested_amount(&vesting, &(cliff_release_timestamp + release_interval_secs)),
+        initial_unlock + cliff_amount + 10
+    );
+
+    env.ledger().set_timestamp(start_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), initial_unlock);
+}
+
+#[test]
+fn test_revoke_before_start_with_initial_unlock() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp - 1);
+    client.revoke_vesting(&admin, &vesting_id);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+
+    // The initial unlock is not vested after a revocation before the start.
+    env.ledger().set_timestamp(end_timestamp);
+    assert_eq!(client.get_claimable_amount(&vesting_id, &end_timestamp), 0);
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert!(client.get_balance() >= client.get_tokens_reserved_for_vesting());
+}
+
+#[test]
+fn test_claims_paused_with_reason() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let reason = Symbol::new(&env, "incident");
+    client.set_claims_paused(&admin, &true, &Some(reason.clone()));
+
+    let pause_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, data)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("CPAUSESET").into_val(&env)]
+                && <(bool, Option<Symbol>)>::try_from_val(&env, data).unwrap()
+                    == (true, Some(reason.clone()))
+        })
+        .count();
+    assert_eq!(pause_events, 1);
+
+    assert!(client.is_claims_paused());
+    assert_eq!(client.get_pause_reason(), Some(reason));
+
+    env.ledger().set_timestamp(end_timestamp);
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert!(client.try_claim_all(&recipient).is_err());
+
+    client.set_claims_paused(&admin, &false, &None);
+    assert!(!client.is_claims_paused());
+    assert_eq!(client.get_pause_reason(), None);
+
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), total_expected_amount);
+}
+
+#[test]
+fn test_revoke_and_settle() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 300);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 1300);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_and_settle(&admin, &vesting_id);
+
+    let mut revoked_events: u32 = 0;
+    let mut claimed_events: Vec<(u64, Address, i128, i128)> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address != client.address {
+            continue;
+        }
+        if topics
+            == vec![
+                &env,
+                symbol_short!("VREVOKED").into_val(&env),
+                recipient.into_val(&env),
+                vesting_id.into_val(&env),
+            ]
+        {
+            revoked_events += 1;
+        }
+        if topics
+            == vec![
+                &env,
+                symbol_short!("CLAIMED").into_val(&env),
+                recipient.into_val(&env),
+                vesting_id.into_val(&env),
+            ]
+        {
+            claimed_events
+                .push_back(<(u64, Address, i128, i128)>::try_from_val(&env, &data).unwrap());
+        }
+    }
+    assert_eq!(revoked_events, 1);
+    assert_eq!(
+        claimed_events,
+        vec![&env, (vesting_id, recipient.clone(), 200, 0)]
+    );
+
+    // The recipient is paid the vested amount and the unvested amount is withdrawable.
+    assert_eq!(token_client.balance(&recipient), 1500);
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 500);
+
+    client.withdraw_admin(&admin, &500);
+    assert_eq!(token_client.balance(&admin), 500);
+}
+
+#[test]
+fn test_revoke_and_settle_timelocked() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = start_timestamp + 800;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // The vesting is revoked, but its vested amount stays claimable until the timelock ends.
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_and_settle(&admin, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 1500);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 500);
+
+    env.ledger().set_timestamp(timelock);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+}
+
+#[test]
+fn test_get_unvested_amount() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(
+        client.get_unvested_amount(&vesting_id, &(start_timestamp - 1)),
+        2000
+    );
+    assert_eq!(
+        client.get_unvested_amount(&vesting_id, &start_timestamp),
+        1000
+    );
+    assert_eq!(
+        client.get_unvested_amount(&vesting_id, &(start_timestamp + 250)),
+        750
+    );
+    assert_eq!(client.get_unvested_amount(&vesting_id, &end_timestamp), 0);
+
+    let revoked_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_vesting(&admin, &revoked_vesting_id);
+
+    assert_eq!(
+        client.get_unvested_amount(&revoked_vesting_id, &start_timestamp),
+        500
+    );
+    assert_eq!(
+        client.get_unvested_amount(&revoked_vesting_id, &(start_timestamp + 500)),
+        0
+    );
+    assert_eq!(
+        client.get_unvested_amount(&revoked_vesting_id, &end_timestamp),
+        0
+    );
+}
+
+#[test]
+fn test_admin_set_claimed() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+
+    // Reserving more than the contract holds is rejected.
+    assert!(client
+        .try_admin_set_claimed(&admin, &vesting_id, &1200)
+        .is_err());
+
+    // The recipient returned the claimed tokens.
+    token_admin_client.mint(&client.address, &1500);
+
+    // Lowering the claimed amount reserves the difference again.
+    client.admin_set_claimed(&admin, &vesting_id, &1200);
+
+    let claimed_set_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, data)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("CLAIMSET").into_val(&env)]
+                && <(u64, Address, i128, i128)>::try_from_val(&env, data).unwrap()
+                    == (vesting_id, admin.clone(), 1500, 1200)
+        })
+        .count();
+    assert_eq!(claimed_set_events, 1);
+
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 1200);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 800);
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(start_timestamp + 500)),
+        300
+    );
+
+    // Raising the claimed amount releases the difference.
+    env.ledger().set_timestamp(end_timestamp);
+    client.admin_set_claimed(&admin, &vesting_id, &2000);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+    assert_eq!(client.get_claimable_amount(&vesting_id, &end_timestamp), 0);
+
+    client.admin_set_claimed(&admin, &vesting_id, &0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 2000);
+
+    // Setting the same value is rejected.
+    assert!(client
+        .try_admin_set_claimed(&admin, &vesting_id, &0)
+        .is_err());
+
+    // Increases above the revoke approval threshold are rejected.
+    client.set_revoke_approval_threshold(&admin, &500);
+    assert!(client
+        .try_admin_set_claimed(&admin, &vesting_id, &600)
+        .is_err());
+    client.admin_set_claimed(&admin, &vesting_id, &500);
+
+    // Irrevocable vestings cannot be changed.
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+    let irrevocable_vesting_id: u64 = client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &0,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert!(client
+        .try_admin_set_claimed(&admin, &irrevocable_vesting_id, &100)
+        .is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_admin_set_claimed_above_vested_total() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Panics because the claimed amount exceeds the final vested total.
+    client.admin_set_claimed(&admin, &vesting_id, &(total_expected_amount + 1));
+}
+
+#[test]
+#[should_panic]
+fn test_admin_set_claimed_negative() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Panics because the claimed amount is negative.
+    client.admin_set_claimed(&admin, &vesting_id, &-1);
+}
+
+#[test]
+fn test_admin_set_claimed_above_vested_now() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Half of the linear amount has vested.
+    env.ledger().set_timestamp(start_timestamp + 500);
+
+    // The claimed amount cannot exceed the amount vested so far.
+    assert!(client
+        .try_admin_set_claimed(&admin, &vesting_id, &1501)
+        .is_err());
+
+    client.admin_set_claimed(&admin, &vesting_id, &1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(start_timestamp + 500)),
+        0
+    );
+
+    // Nothing is left to claim, and claiming leaves the accounting untouched.
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert_eq!(client.claim_all(&recipient), 0);
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 1500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+
+    // Claims resume once more has vested.
+    env.ledger().set_timestamp(end_timestamp);
+    assert_eq!(client.claim_all(&recipient), 500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_admin_set_claimed_not_admin() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Panics because only admins can correct the claimed amount.
+    client.admin_set_claimed(&recipient, &vesting_id, &100);
+}
+
+#[test]
+fn test_max_vestings_per_recipient() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    client.set_max_vestings_per_recipient(&admin, &2);
+    assert_eq!(client.get_max_vestings_per_recipient(), 2);
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Other recipients are not affected by the cap of the first one.
+    let other_recipient: Address = Address::generate(&env);
+    client.create_vesting(
+        &admin,
+        &other_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(client.get_all_recipient_vestings_len(&recipient), 2);
+    assert_eq!(client.get_all_recipient_vestings_len(&other_recipient), 1);
+}
+
+#[test]
+#[should_panic]
+fn test_max_vestings_per_recipient_exceeded() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    client.set_max_vestings_per_recipient(&admin, &2);
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Panics because the recipient already has the maximum number of vestings.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+fn test_create_and_push_initial() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 300;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    env.ledger().set_timestamp(start_timestamp);
+
+    let vesting_id: u64 = client.create_and_push_initial(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // The initial unlock is received in the same call.
+    assert_eq!(token_client.balance(&recipient), initial_unlock);
+    assert_eq!(
+        client.get_vesting_info(&vesting_id).claimed_amount,
+        initial_unlock
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), linear_vest_amount);
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &start_timestamp),
+        0
+    );
+
+    // Nothing is pushed for a vesting starting in the future.
+    let future_vesting_id: u64 = client.create_and_push_initial(
+        &admin,
+        &recipient,
+        &(start_timestamp + 100),
+        &(end_timestamp + 100),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(token_client.balance(&recipient), initial_unlock);
+    assert_eq!(
+        client.get_vesting_info(&future_vesting_id).claimed_amount,
+        0
+    );
+}
+
+#[test]
+fn test_recipient_vestings_hard_limit() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let linear_vest_amount: i128 = 100;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 101));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 101),
+        &expiration_ledger,
+    );
+
+    // The configured maximum cannot exceed the hard limit.
+    assert!(client
+        .try_set_max_vestings_per_recipient(&admin, &101)
+        .is_err());
+
+    for _ in 0..100 {
+        client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &release_interval_secs,
+            &linear_vest_amount,
+        );
+    }
+
+    // Without a configured maximum, the hard limit applies.
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    // Claiming all the vestings of a recipient at the hard limit fits in a single call.
+    env.ledger().set_timestamp(end_timestamp);
+    assert_eq!(client.claim_all(&recipient), linear_vest_amount * 100);
+}
+
+#[test]
+fn test_revoke_amount_remaining() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 800;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let revoke_timestamp: u64 = start_timestamp + 605;
+    env.ledger().set_timestamp(revoke_timestamp);
+    client.claim(&recipient, &vesting_id);
+
+    // 40 of the 80 linear intervals have elapsed, the last 5 seconds being truncated.
+    let vested_amount: i128 = initial_unlock + cliff_amount + 400;
+    let unvested_amount: i128 = total_expected_amount - vested_amount;
+    assert_eq!(token_client.balance(&recipient), vested_amount);
+
+    client.revoke_vesting(&admin, &vesting_id);
+
+    let revoked_amounts: Vec<i128> = {
+        let mut amounts: Vec<i128> = Vec::new(&env);
+        for (contract_address, topics, data) in env.events().all().iter() {
+            if contract_address == client.address
+                && topics
+                    == vec![
+                        &env,
+                        symbol_short!("VREVOKED").into_val(&env),
+                        recipient.into_val(&env),
+                        vesting_id.into_val(&env),
+                    ]
+            {
+                let (_, _, amount_remaining, _, reserved_tokens) =
+                    <(u64, Address, i128, Vesting, i128)>::try_from_val(&env, &data).unwrap();
+                assert_eq!(reserved_tokens, client.get_tokens_reserved_for_vesting());
+                amounts.push_back(amount_remaining);
+            }
+        }
+        amounts
+    };
+    assert_eq!(revoked_amounts, vec![&env, unvested_amount]);
+
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+    assert_eq!(client.amount_to_withdraw_by_admin(), unvested_amount);
+    assert_eq!(client.get_claimable_amount(&vesting_id, &end_timestamp), 0);
+}
+
+#[test]
+fn test_get_all_recipient_vesting_sliced_clamped() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..2 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    // Unknown recipients get an empty list whatever the bounds.
+    let unknown_recipient: Address = Address::generate(&env);
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&0, &0, &unknown_recipient),
+        Vec::new(&env)
+    );
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&0, &10, &unknown_recipient),
+        Vec::new(&env)
+    );
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&5, &2, &unknown_recipient),
+        Vec::new(&env)
+    );
+
+    // Out of range bounds are clamped for known recipients.
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&0, &10, &recipient),
+        vesting_ids
+    );
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&1, &10, &recipient),
+        vec![&env, vesting_ids.get(1).unwrap()]
+    );
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&5, &10, &recipient),
+        Vec::new(&env)
+    );
+    assert_eq!(
+        client.get_all_recipient_vesting_sliced(&2, &1, &recipient),
+        Vec::new(&env)
+    );
+}
+
+#[test]
+fn test_vesting_event_topics() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let mut created_reserved: Vec<i128> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == client.address
+            && topics
+                == vec![
+                    &env,
+                    symbol_short!("VCREATED").into_val(&env),
+                    recipient.into_val(&env),
+                    vesting_id.into_val(&env),
+                ]
+        {
+            let (_, _, _, reserved_tokens) =
+                <(u64, Address, Vesting, i128)>::try_from_val(&env, &data).unwrap();
+            created_reserved.push_back(reserved_tokens);
+        }
+    }
+    // The event carries the reserved total after the creation.
+    assert_eq!(
+        created_reserved,
+        vec![&env, client.get_tokens_reserved_for_vesting()]
+    );
+    assert_eq!(created_reserved.get(0).unwrap(), total_expected_amount);
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+
+    let mut claimed_amounts: Vec<i128> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address != client.address {
+            continue;
+        }
+        // Soroban events are limited to 4 topics.
+        assert!(topics.len() <= 4);
+        if topics
+            == vec![
+                &env,
+                symbol_short!("CLAIMED").into_val(&env),
+                recipient.into_val(&env),
+                vesting_id.into_val(&env),
+            ]
+        {
+            let (_, _, amount, reserved_tokens) =
+                <(u64, Address, i128, i128)>::try_from_val(&env, &data).unwrap();
+            assert_eq!(reserved_tokens, client.get_tokens_reserved_for_vesting());
+            claimed_amounts.push_back(amount);
+        }
+    }
+    assert_eq!(claimed_amounts, vec![&env, total_expected_amount]);
+}
+
+#[test]
+fn test_claim_hook() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let recording_hook_address = env.register(MockClaimHook, ());
+    let recording_hook = MockClaimHookClient::new(&env, &recording_hook_address);
+    let failing_hook_address = env.register(MockClaimHook, ());
+    MockClaimHookClient::new(&env, &failing_hook_address).set_failing(&true);
+
+    assert_eq!(client.get_claim_hook(&recipient), None);
+    client.set_claim_hook(&recipient, &Some(recording_hook_address.clone()));
+    assert_eq!(
+        client.get_claim_hook(&recipient),
+        Some(recording_hook_address.clone())
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 500);
+    assert_eq!(recording_hook.last_call(), Some((recipient.clone(), 500)));
+
+    // A failing hook reverts the whole claim.
+    client.set_claim_hook(&recipient, &Some(failing_hook_address));
+    env.ledger().set_timestamp(start_timestamp + 700);
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert_eq!(token_client.balance(&recipient), 500);
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+
+    // Clearing the hook restores plain claims.
+    client.set_claim_hook(&recipient, &None);
+    assert_eq!(client.get_claim_hook(&recipient), None);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 700);
+    assert_eq!(recording_hook.last_call(), Some((recipient.clone(), 500)));
+
+    // Claiming all vestings calls the hook with the total claimed.
+    client.set_claim_hook(&recipient, &Some(recording_hook_address.clone()));
+    env.ledger().set_timestamp(start_timestamp + 900);
+    assert_eq!(client.claim_all(&recipient), 200);
+    assert_eq!(recording_hook.last_call(), Some((recipient.clone(), 200)));
+
+    // Amounts settled on revocation are claims as well.
+    env.ledger().set_timestamp(start_timestamp + 950);
+    client.revoke_and_settle(&admin, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 950);
+    assert_eq!(recording_hook.last_call(), Some((recipient.clone(), 50)));
+
+    // Each hook is stored under its own entry.
+    env.as_contract(&client.address, || {
+        assert_eq!(
+            env.storage()
+                .persistent()
+                .get::<_, Address>(&(CLAIM_HOOKS, recipient.clone())),
+            Some(recording_hook_address.clone())
+        );
+    });
+}
+
+#[test]
+fn test_calculate_vested_amount_rounding_high_decimals() {
+    let env = Env::default();
+    let start_timestamp: u64 = 1000;
+    let release_interval_secs: u64 = 7;
+    let number_of_intervals: u64 = 429;
+    let end_timestamp: u64 = start_timestamp + release_interval_secs * number_of_intervals;
+
+    for decimals in 0..=18_u32 {
+        let unit: i128 = 10_i128.pow(decimals);
+        for linear_vest_amount in [
+            unit + 7,
+            unit * 1_000 - 1,
+            unit * 123_456_789 + number_of_intervals as i128 - 1,
+            unit * 1_000_000_000,
+        ] {
+            let vesting = Vesting {
+                recipient: Address::generate(&env),
+                start_timestamp,
+                end_timestamp,
+                deactivation_timestamp: 0,
+                timelock: 0,
+                release_interval_secs,
+                cliff_release_timestamp: 0,
+                initial_unlock: 0,
+                cliff_amount: 0,
+                linear_vest_amount,
+                claimed_amount: 0,
+            };
+
+            let mut previous_amount: i128 = 0;
+            for elapsed_intervals in [1_u64, 2, 13, 100, 214, 333, 428] {
+                let reference_timestamp =
+                    start_timestamp + elapsed_intervals * release_interval_secs + 3;
+                let vested_amount = TokenVestingManager::calculate_vested_amount(
+                    env.clone(),
+                    vesting.clone(),
+                    reference_timestamp,
+                );
+
+                // The rounding error against the exact proportional amount stays under one base unit.
+                let exact_numerator = linear_vest_amount * elapsed_intervals as i128;
+                let denominator = number_of_intervals as i128;
+                assert!(vested_amount * denominator <= exact_numerator);
+                assert!(exact_numerator - vested_amount * denominator < denominator);
+
+                assert!(vested_amount >= previous_amount);
+                previous_amount = vested_amount;
+            }
+
+            // The exact total is vested at the end.
+            assert_eq!(
+                TokenVestingManager::calculate_vested_amount(
+                    env.clone(),
+                    vesting.clone(),
+                    end_timestamp
+                ),
+                linear_vest_amount
+            );
+        }
+    }
+}
+
+#[test]
+fn test_recipient_allowlist() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let allowed_recipient: Address = Address::generate(&env);
+    let other_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    // Any recipient is allowed while the allowlist is disabled.
+    assert!(!client.is_recipient_allowlist_enabled());
+    assert!(client.is_recipient_allowed(&other_recipient));
+
+    client.set_recipient_allowlist_enabled(&admin, &true);
+    client.set_recipient_allowed(&admin, &allowed_recipient, &true);
+
+    assert!(client.is_recipient_allowlist_enabled());
+    assert!(client.is_recipient_allowed(&allowed_recipient));
+    assert!(!client.is_recipient_allowed(&other_recipient));
+
+    client.create_vesting(
+        &admin,
+        &allowed_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &other_recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    // Removing a recipient from the allowlist blocks further vestings.
+    client.set_recipient_allowed(&admin, &allowed_recipient, &false);
+    assert!(!client.is_recipient_allowed(&allowed_recipient));
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &allowed_recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    // Disabling the allowlist lifts the restriction.
+    client.set_recipient_allowlist_enabled(&admin, &false);
+    client.create_vesting(
+        &admin,
+        &other_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(client.get_all_recipient_vestings_len(&other_recipient), 1);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_set_recipient_allowed_not_admin() {
+    let env = Env::default();
+    let (client, _, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let not_admin: Address = Address::generate(&env);
+    client.set_recipient_allowed(&not_admin, &Address::generate(&env), &true);
+}
+
+#[test]
+fn test_get_vesting_funder() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let other_admin: Address = Address::generate(&env);
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admins.
+    env.mock_all_auths();
+    client.set_admin(&admin, &other_admin, &true);
+    for funder in [admin.clone(), other_admin.clone()] {
+        token_admin_client.mint(&funder, &total_expected_amount);
+        token_client.approve(
+            &funder,
+            &client.address,
+            &total_expected_amount,
+            &expiration_ledger,
+        );
+    }
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let other_vesting_id: u64 = client.create_vesting(
+        &other_admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(client.get_vesting_funder(&vesting_id), admin);
+    assert_eq!(client.get_vesting_funder(&other_vesting_id), other_admin);
+    assert!(client
+        .try_get_vesting_funder(&(other_vesting_id + 1))
+        .is_err());
+}
+
+#[test]
+fn test_sunset() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let revoked_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert!(!client.is_sunset());
+    client.sunset(&admin);
+
+    let sunset_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("SUNSETSET").into_val(&env)]
+        })
+        .count();
+    assert_eq!(sunset_events, 1);
+
+    assert!(client.is_sunset());
+
+    // The sunset cannot be set twice, nor undone.
+    assert!(client.try_sunset(&admin).is_err());
+
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    let vesting_params = CreateVestingBatchParams {
+        recipients: vec![&env, recipient.clone()],
+        start_timestamps: vec![&env, start_timestamp],
+        end_timestamps: vec![&env, end_timestamp],
+        timelocks: vec![&env, timelock],
+        initial_unlocks: vec![&env, initial_unlock],
+        cliff_release_timestamps: vec![&env, cliff_release_timestamp],
+        cliff_amounts: vec![&env, cliff_amount],
+        release_interval_secs: vec![&env, release_interval_secs],
+        linear_vest_amounts: vec![&env, linear_vest_amount],
+    };
+    assert!(client
+        .try_create_vesting_batch(&admin, &vesting_params)
+        .is_err());
+
+    // Existing vestings can still be claimed and revoked.
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 500);
+
+    client.revoke_vesting(&admin, &revoked_vesting_id);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 500);
+    assert!(client.is_sunset());
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_sunset_not_admin() {
+    let env = Env::default();
+    let (client, _, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let not_admin: Address = Address::generate(&env);
+    client.sunset(&not_admin);
+}
+
+#[test]
+fn test_get_vesting_info_or_default() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // No vesting has been created yet.
+    assert_eq!(client.get_vesting_info_or_default(&0), None);
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(
+        client.get_vesting_info_or_default(&vesting_id),
+        Some(client.get_vesting_info(&vesting_id))
+    );
+    assert_eq!(client.get_vesting_info_or_default(&(vesting_id + 1)), None);
+    assert!(client.try_get_vesting_info(&(vesting_id + 1)).is_err());
+}
+
+#[test]
+fn test_revoke_approval_threshold() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let other_admin: Address = Address::generate(&env);
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admins.
+    env.mock_all_auths();
+    client.set_admin(&admin, &other_admin, &true);
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let small_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let large_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    client.set_revoke_approval_threshold(&admin, &500);
+    assert_eq!(client.get_revoke_approval_threshold(), 500);
+
+    // Claim most of the first vesting so its unvested amount falls below the threshold.
+    env.ledger().set_timestamp(start_timestamp + 800);
+    client.claim(&recipient, &small_vesting_id);
+    client.revoke_vesting(&admin, &small_vesting_id);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 200);
+
+    // Revoking a large amount needs a second admin.
+    env.ledger().set_timestamp(start_timestamp + 100);
+    assert!(client
+        .try_revoke_vesting(&admin, &large_vesting_id)
+        .is_err());
+    assert!(client
+        .try_revoke_vesting_refund_to(&admin, &large_vesting_id, &admin)
+        .is_err());
+    assert!(client
+        .try_revoke_and_settle(&admin, &large_vesting_id)
+        .is_err());
+    assert!(client
+        .try_approve_revocation(&other_admin, &large_vesting_id)
+        .is_err());
+
+    client.propose_revocation(&admin, &large_vesting_id);
+    assert_eq!(
+        client.get_pending_revocation(&large_vesting_id),
+        Some(admin.clone())
+    );
+    assert!(client
+        .try_propose_revocation(&other_admin, &large_vesting_id)
+        .is_err());
+
+    // The proposer cannot approve their own revocation.
+    assert!(client
+        .try_approve_revocation(&admin, &large_vesting_id)
+        .is_err());
+    assert_eq!(
+        client
+            .get_vesting_info(&large_vesting_id)
+            .deactivation_timestamp,
+        0
+    );
+
+    client.approve_revocation(&other_admin, &large_vesting_id);
+    assert_eq!(
+        client
+            .get_vesting_info(&large_vesting_id)
+            .deactivation_timestamp,
+        start_timestamp + 100
+    );
+    assert_eq!(client.get_pending_revocation(&large_vesting_id), None);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 200 + 900);
+
+    // Raising or disabling the threshold needs a second admin, lowering it does not.
+    client.set_revoke_approval_threshold(&admin, &0);
+    assert_eq!(client.get_revoke_approval_threshold(), 500);
+    assert_eq!(
+        client.get_pending_revoke_threshold(),
+        Some((0, admin.clone()))
+    );
+    client.set_revoke_approval_threshold(&admin, &0);
+    assert_eq!(client.get_revoke_approval_threshold(), 500);
+    client.set_revoke_approval_threshold(&other_admin, &0);
+    assert_eq!(client.get_revoke_approval_threshold(), 0);
+    assert_eq!(client.get_pending_revoke_threshold(), None);
+
+    client.set_revoke_approval_threshold(&admin, &300);
+    assert_eq!(client.get_revoke_approval_threshold(), 300);
+    client.set_revoke_approval_threshold(&admin, &100);
+    assert_eq!(client.get_revoke_approval_threshold(), 100);
+    client.set_revoke_approval_threshold(&admin, &1000);
+    assert_eq!(client.get_revoke_approval_threshold(), 100);
+    client.set_revoke_approval_threshold(&other_admin, &1000);
+    assert_eq!(client.get_revoke_approval_threshold(), 1000);
+}
+
+#[test]
+fn test_mandatory_destination() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let destination: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(client.get_mandatory_destination(&recipient), None);
+    client.set_mandatory_destination(&admin, &recipient, &Some(destination.clone()));
+    assert_eq!(
+        client.get_mandatory_destination(&recipient),
+        Some(destination.clone())
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 300);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&destination), 300);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim_all(&recipient);
+    assert_eq!(token_client.balance(&destination), 500);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // Clearing the destination sends claims to the recipient again.
+    client.set_mandatory_destination(&admin, &recipient, &None);
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&destination), 500);
+    assert_eq!(token_client.balance(&recipient), 500);
+
+    // Only admins can set a mandatory destination.
+    assert!(client
+        .try_set_mandatory_destination(&recipient, &recipient, &Some(destination))
+        .is_err());
+}
+
+#[test]
+fn test_get_all_recipients_sliced_empty() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    // A freshly initialized contract has no recipients.
+    assert_eq!(client.get_all_recipients_sliced(&0, &0), Vec::new(&env));
+    assert_eq!(client.get_all_recipients_sliced(&0, &5), Vec::new(&env));
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(client.get_all_recipients_sliced(&1, &1), Vec::new(&env));
+    assert_eq!(client.get_all_recipients_sliced(&1, &0), Vec::new(&env));
+    assert_eq!(
+        client.get_all_recipients_sliced(&0, &1),
+        vec![&env, recipient.clone()]
+    );
+
+    // The range is clamped to the recipients, but cannot span more than a page.
+    assert_eq!(
+        client.get_all_recipients_sliced(&0, &100),
+        vec![&env, recipient]
+    );
+    assert!(client.try_get_all_recipients_sliced(&0, &101).is_err());
+}
+
+#[test]
+fn test_get_all_recipients_over_page() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient_count: u32 = 101;
+    let linear_vest_amount: i128 = 1000;
+    let total_expected_amount: i128 = linear_vest_amount * recipient_count as i128;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    for _ in 0..recipient_count {
+        client.create_vesting(
+            &admin,
+            &Address::generate(&env),
+            &1000,
+            &2000,
+            &0,
+            &0,
+            &0,
+            &0,
+            &10,
+            &linear_vest_amount,
+        );
+    }
+
+    assert_eq!(client.get_all_recipients_len(), recipient_count);
+    assert_eq!(client.get_all_recipients().len(), recipient_count);
+
+    // The bounded read returns the first page and flags the truncation.
+    let (first_page, truncated) = client.get_all_recipients_bounded();
+    assert_eq!(first_page, client.get_all_recipients_sliced(&0, &100));
+    assert!(truncated);
+    assert_eq!(client.get_all_recipients_sliced(&100, &101).len(), 1);
+
+    // A single page is not truncated.
+    env.ledger().set_timestamp(2000);
+    let last_recipient = client.get_all_recipients_sliced(&100, &101).get(0).unwrap();
+    let last_vesting_id = client
+        .get_all_recipient_vestings(&last_recipient)
+        .get(0)
+        .unwrap();
+    client.claim(&last_recipient, &last_vesting_id);
+    client.purge_vesting(&admin, &last_vesting_id);
+    assert_eq!(
+        client.get_all_recipients_bounded(),
+        (client.get_all_recipients(), false)
+    );
+}
+
+#[test]
+fn test_reconfigure_token() {
+    let env = Env::default();
+    let (client, admin, _, _, token_address) = deploy_manager_helper(&env);
+    let (new_token_client, new_token_admin_client, new_token_address) = deploy_token_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    assert!(client
+        .try_reconfigure_token(&admin, &token_address)
+        .is_err());
+
+    client.reconfigure_token(&admin, &new_token_address);
+
+    let reconfigured_events: Vec<(Address, Address)> = {
+        let mut events: Vec<(Address, Address)> = Vec::new(&env);
+        for (contract_address, topics, data) in env.events().all().iter() {
+            if contract_address == client.address
+                && topics == vec![&env, symbol_short!("TOKENRECF").into_val(&env)]
+            {
+                events.push_back(<(Address, Address)>::try_from_val(&env, &data).unwrap());
+            }
+        }
+        events
+    };
+    assert_eq!(
+        reconfigured_events,
+        vec![&env, (token_address.clone(), new_token_address.clone())]
+    );
+
+    assert_eq!(client.get_token_address(), new_token_address);
+
+    // Once funded, the token cannot be changed anymore.
+    new_token_admin_client.mint(&client.address, &1);
+    assert_eq!(new_token_client.balance(&client.address), 1);
+    assert!(client
+        .try_reconfigure_token(&admin, &token_address)
+        .is_err());
+    assert_eq!(client.get_token_address(), new_token_address);
+}
+
+#[test]
+fn test_reconfigure_token_after_vesting_created() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let (_, _, new_token_address) = deploy_token_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    // Even once the vesting is fully claimed and the contract is empty again.
+    env.ledger().set_timestamp(2000);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    assert!(client
+        .try_reconfigure_token(&admin, &new_token_address)
+        .is_err());
+}
+
+#[test]
+fn test_create_vesting_batch_invalid_row_applies_nothing() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipients = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 3),
+        &expiration_ledger,
+    );
+
+    // The middle row ends before it starts.
+    let vesting_params = CreateVestingBatchParams {
+        recipients: recipients.clone(),
+        start_timestamps: vec![&env, 1000, 2000, 1000],
+        end_timestamps: vec![&env, 2000, 1000, 2000],
+        timelocks: vec![&env, 0, 0, 0],
+        initial_unlocks: vec![&env, 0, 0, 0],
+        cliff_release_timestamps: vec![&env, 0, 0, 0],
+        cliff_amounts: vec![&env, 0, 0, 0],
+        release_interval_secs: vec![&env, 10, 10, 10],
+        linear_vest_amounts: vec![
+            &env,
+            linear_vest_amount,
+            linear_vest_amount,
+            linear_vest_amount,
+        ],
+    };
+    assert!(client
+        .try_create_vesting_batch(&admin, &vesting_params)
+        .is_err());
+
+    assert_eq!(client.get_all_recipients_len(), 0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+    assert_eq!(token_client.balance(&client.address), 0);
+    assert_eq!(token_client.balance(&admin), linear_vest_amount * 3);
+
+    // A batch exceeding the allowance is rejected as a whole too.
+    let vesting_params = CreateVestingBatchParams {
+        recipients,
+        start_timestamps: vec![&env, 1000, 1000, 1000],
+        end_timestamps: vec![&env, 2000, 2000, 2000],
+        timelocks: vec![&env, 0, 0, 0],
+        initial_unlocks: vec![&env, 0, 0, 0],
+        cliff_release_timestamps: vec![&env, 0, 0, 0],
+        cliff_amounts: vec![&env, 0, 0, 0],
+        release_interval_secs: vec![&env, 10, 10, 10],
+        linear_vest_amounts: vec![
+            &env,
+            linear_vest_amount,
+            linear_vest_amount,
+            linear_vest_amount + 1,
+        ],
+    };
+    assert!(client
+        .try_create_vesting_batch(&admin, &vesting_params)
+        .is_err());
+
+    assert_eq!(client.get_all_recipients_len(), 0);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Invalid start timestamp")]
+fn test_create_vesting_batch_invalid_middle_row() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 3),
+        &expiration_ledger,
+    );
+
+    // The middle row ends before it starts.
+    let vesting_params = CreateVestingBatchParams {
+        recipients: vec![
+            &env,
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ],
+        start_timestamps: vec![&env, 1000, 2000, 1000],
+        end_timestamps: vec![&env, 2000, 1000, 2000],
+        timelocks: vec![&env, 0, 0, 0],
+        initial_unlocks: vec![&env, 0, 0, 0],
+        cliff_release_timestamps: vec![&env, 0, 0, 0],
+        cliff_amounts: vec![&env, 0, 0, 0],
+        release_interval_secs: vec![&env, 10, 10, 10],
+        linear_vest_amounts: vec![
+            &env,
+            linear_vest_amount,
+            linear_vest_amount,
+            linear_vest_amount,
+        ],
+    };
+    client.create_vesting_batch(&admin, &vesting_params);
+}
+
+#[test]
+fn test_get_admin_change_history() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let second_admin: Address = Address::generate(&env);
+    let third_admin: Address = Address::generate(&env);
+
+    // Mock the admins.
+    env.mock_all_auths();
+
+    assert_eq!(client.get_admin_change_history().len(), 0);
+
+    env.ledger().set_timestamp(100);
+    client.set_admin(&admin, &second_admin, &true);
+    env.ledger().set_timestamp(200);
+    client.set_admin(&second_admin, &admin, &false);
+    env.ledger().set_timestamp(300);
+    client.replace_admins(&second_admin, &vec![&env, third_admin.clone()]);
+
+    assert_eq!(
+        client.get_admin_change_history(),
+        vec![
+            &env,
+            (100, admin.clone(), second_admin.clone(), true),
+            (200, second_admin.clone(), admin.clone(), false),
+            (300, second_admin.clone(), second_admin.clone(), false),
+            (300, second_admin.clone(), third_admin.clone(), true),
+        ]
+    );
+
+    // Only the most recent changes are kept.
+    for _ in 0..10 {
+        client.set_admin(&third_admin, &admin, &true);
+        client.set_admin(&third_admin, &admin, &false);
+    }
+    let history = client.get_admin_change_history();
+    assert_eq!(history.len(), 20);
+    assert_eq!(
+        history.get(0).unwrap(),
+        (300, third_admin.clone(), admin.clone(), true)
+    );
+    assert_eq!(history.get(19).unwrap(), (300, third_admin, admin, false));
+}
+
+#[test]
+fn test_min_duration_secs() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    assert_eq!(client.get_min_duration_secs(), 0);
+    client.set_min_duration_secs(&admin, &1000);
+    assert_eq!(client.get_min_duration_secs(), 1000);
+
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &(start_timestamp + 990),
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &(start_timestamp + 1000),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // A minimum of 0 disables the check.
+    client.set_min_duration_secs(&admin, &0);
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &(start_timestamp + 20),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(client.get_all_recipient_vestings_len(&recipient), 2);
+}
+
+#[test]
+fn test_calculate_vested_amount_before_start() {
+    let env = Env::default();
+
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+
+    let linear_vesting = Vesting {
+        recipient: Address::generate(&env),
+        start_timestamp,
+        end_timestamp,
+        deactivation_timestamp: 0,
+        timelock: 0,
+        release_interval_secs: 10,
+        cliff_release_timestamp: 0,
+        initial_unlock: 100,
+        cliff_amount: 0,
+        linear_vest_amount: 1000,
+        claimed_amount: 0,
+    };
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(env.clone(), linear_vesting.clone(), 0),
+        0
+    );
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(
+            env.clone(),
+            linear_vesting.clone(),
+            start_timestamp
+        ),
+        100
+    );
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(
+            env.clone(),
+            linear_vesting.clone(),
+            start_timestamp + 10
+        ),
+        110
+    );
+
+    // The cliff is released at the start.
+    let cliff_vesting = Vesting {
+        cliff_release_timestamp: start_timestamp,
+        cliff_amount: 200,
+        ..linear_vesting.clone()
+    };
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(env.clone(), cliff_vesting.clone(), 0),
+        0
+    );
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(
+            env.clone(),
+            cliff_vesting.clone(),
+            start_timestamp
+        ),
+        300
+    );
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(
+            env.clone(),
+            cliff_vesting.clone(),
+            end_timestamp
+        ),
+        1300
+    );
+
+    // A vesting ending at its start has no linear part to compute.
+    let empty_vesting = Vesting {
+        end_timestamp: start_timestamp,
+        ..linear_vesting
+    };
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(env.clone(), empty_vesting.clone(), 0),
+        0
+    );
+    assert_eq!(
+        TokenVestingManager::calculate_vested_amount(
+            env.clone(),
+            empty_vesting.clone(),
+            end_timestamp
+        ),
+        100
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid release interval")]
+fn test_calculate_vested_amount_interval_longer_than_duration() {
+    let env = Env::default();
+
+    let vesting = Vesting {
+        recipient: Address::generate(&env),
+        start_timestamp: 1000,
+        end_timestamp: 2000,
+        deactivation_timestamp: 0,
+        timelock: 0,
+        release_interval_secs: 5000,
+        cliff_release_timestamp: 0,
+        initial_unlock: 0,
+        cliff_amount: 0,
+        linear_vest_amount: 1000,
+        claimed_amount: 0,
+    };
+    TokenVestingManager::calculate_vested_amount(env.clone(), vesting.clone(), 1500);
+}
+
+#[test]
+fn test_irrevocable_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    let irrevocable_vesting_id: u64 = client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let revocable_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert!(client.is_irrevocable(&irrevocable_vesting_id));
+    assert!(!client.is_irrevocable(&revocable_vesting_id));
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    assert!(client
+        .try_revoke_vesting(&admin, &irrevocable_vesting_id)
+        .is_err());
+    assert!(client
+        .try_revoke_vesting_refund_to(&admin, &irrevocable_vesting_id, &admin)
+        .is_err());
+    assert!(client
+        .try_revoke_and_settle(&admin, &irrevocable_vesting_id)
+        .is_err());
+    assert_eq!(
+        client
+            .get_vesting_info(&irrevocable_vesting_id)
+            .deactivation_timestamp,
+        0
+    );
+
+    client.revoke_vesting(&admin, &revocable_vesting_id);
+    assert_eq!(
+        client
+            .get_vesting_info(&revocable_vesting_id)
+            .deactivation_timestamp,
+        start_timestamp + 500
+    );
+}
+
+#[test]
+#[should_panic(expected = "Vesting irrevocable")]
+fn test_revoke_irrevocable_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    client.revoke_vesting(&admin, &vesting_id);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_upgrade_not_admin() {
+    let env = Env::default();
+    let (client, _, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let not_admin: Address = Address::generate(&env);
+    let new_wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    client.upgrade(&not_admin, &new_wasm_hash);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient approvals")]
+fn test_upgrade_with_threshold_single_approval() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+
+    let new_wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    client.propose_action(
+        &admin,
+        &upgrade_action_hash(&env, admin.clone(), new_wasm_hash.clone()),
+    );
+
+    // Panics because only one admin approved the upgrade.
+    client.upgrade(&admin, &new_wasm_hash);
+}
+
+#[test]
+#[should_panic(expected = "Admin change delay not elapsed")]
+fn test_upgrade_before_admin_change_delay() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    env.ledger().set_timestamp(1000);
+    client.set_admin_change_delay(&admin, &3600);
+
+    // An unproposed hash cannot be installed.
+    let new_wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    assert!(client.try_upgrade(&admin, &new_wasm_hash).is_err());
+
+    client.propose_upgrade(&admin, &new_wasm_hash);
+    assert_eq!(
+        client.get_pending_upgrade(),
+        (new_wasm_hash.clone(), 1000 + 3600)
+    );
+
+    // Panics because the delay has not elapsed yet.
+    env.ledger().set_timestamp(1000 + 3599);
+    client.upgrade(&admin, &new_wasm_hash);
+}
+
+#[test]
+fn test_migrate() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    // Fresh contracts start at the current storage version.
+    assert_eq!(client.migration_status(), (1, 1));
+    assert!(client.try_migrate(&admin).is_err());
+
+    // Contracts initialized before the version was tracked have none.
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&STORAGE_VERSION);
+    });
+    assert_eq!(client.get_storage_version(), 0);
+    assert_eq!(client.migration_status(), (1, 0));
+
+    let not_admin: Address = Address::generate(&env);
+    assert!(client.try_migrate(&not_admin).is_err());
+
+    client.migrate(&admin);
+
+    let migrated_event = env
+        .events()
+        .all()
+        .iter()
+        .find(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("MIGRATED").into_val(&env)]
+        })
+        .map(|(_, _, data)| <(u32, u32)>::try_from_val(&env, &data).unwrap());
+    assert_eq!(migrated_event, Some((0, 1)));
+
+    assert_eq!(client.get_storage_version(), 1);
+    assert_eq!(client.migration_status(), (1, 1));
+
+    // Migrating twice is rejected.
+    assert!(client.try_migrate(&admin).is_err());
+}
+
+#[test]
+fn test_claim_compound() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+
+    // Only the recipient can compound its claim.
+    let not_recipient: Address = Address::generate(&env);
+    assert!(client
+        .try_claim_compound(&not_recipient, &vesting_id, &100)
+        .is_err());
+
+    let compounded_vesting_id: u64 = client.claim_compound(&recipient, &vesting_id, &100);
+
+    // No claim event is published as no tokens leave the contract.
+    let claimed_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            *contract_address == client.address
+                && *topics
+                    == vec![
+                        &env,
+                        symbol_short!("CLAIMED").into_val(&env),
+                        recipient.into_val(&env),
+                        vesting_id.into_val(&env),
+                    ]
+        })
+        .count();
+    assert_eq!(claimed_events, 0);
+
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 500);
+    // Nothing was paid out, so the claimed total is unchanged.
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 0);
+
+    // Nothing left to compound until more tokens vest.
+    assert!(client
+        .try_claim_compound(&recipient, &vesting_id, &100)
+        .is_err());
+
+    let compounded_vesting = client.get_vesting_info(&compounded_vesting_id);
+    assert_eq!(compounded_vesting.recipient, recipient);
+    assert_eq!(compounded_vesting.start_timestamp, start_timestamp + 500);
+    assert_eq!(compounded_vesting.end_timestamp, start_timestamp + 600);
+    assert_eq!(compounded_vesting.linear_vest_amount, 500);
+    assert_eq!(compounded_vesting.claimed_amount, 0);
+    assert_eq!(client.get_vesting_funder(&compounded_vesting_id), recipient);
+    assert!(client.is_irrevocable(&compounded_vesting_id));
+    assert!(client
+        .try_revoke_vesting(&admin, &compounded_vesting_id)
+        .is_err());
+    assert_eq!(
+        client.get_all_recipient_vestings(&recipient),
+        vec![&env, vesting_id, compounded_vesting_id]
+    );
+
+    // The tokens stayed in the contract and remain reserved.
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(token_client.balance(&client.address), total_expected_amount);
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 550);
+    assert_eq!(
+        client.get_claimable_amount(&compounded_vesting_id, &(start_timestamp + 550)),
+        250
+    );
+
+    client.claim(&recipient, &compounded_vesting_id);
+    assert_eq!(token_client.balance(&recipient), 250);
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 250);
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount - 250
+    );
+}
+
+#[test]
+fn test_admin_change_after_delay() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let new_admin: Address = Address::generate(&env);
+    let delay_secs: u64 = 3600;
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    client.set_admin_change_delay(&admin, &delay_secs);
+    assert_eq!(client.get_admin_change_delay(), delay_secs);
+
+    // Direct admin changes are disabled while a delay is set.
+    assert!(client.try_set_admin(&admin, &new_admin, &true).is_err());
+    assert!(client
+        .try_replace_admins(&admin, &vec![&env, new_admin.clone()])
+        .is_err());
+
+    env.ledger().set_timestamp(1000);
+    client.propose_admin_change(&admin, &new_admin, &true);
+
+    let pending_admin_change = client.get_pending_admin_change();
+    assert_eq!(pending_admin_change.proposer, admin);
+    assert_eq!(pending_admin_change.admin, new_admin);
+    assert!(pending_admin_change.is_enabled);
+    assert_eq!(pending_admin_change.ready_timestamp, 1000 + delay_secs);
+
+    // Only one admin change can be pending at a time.
+    assert!(client
+        .try_propose_admin_change(&admin, &Address::generate(&env), &true)
+        .is_err());
+
+    env.ledger().set_timestamp(1000 + delay_secs);
+    client.execute_admin_change(&admin);
+
+    assert!(client.is_admin(&new_admin));
+    assert_eq!(client.get_admins_count(), 2);
+    assert!(client.try_get_pending_admin_change().is_err());
+
+    // The new admin can remove the former one through the same delay.
+    client.propose_admin_change(&new_admin, &admin, &false);
+    env.ledger().set_timestamp(1000 + delay_secs * 2);
+    client.execute_admin_change(&new_admin);
+
+    assert!(!client.is_admin(&admin));
+    assert_eq!(client.get_admins_count(), 1);
+
+    // Shortening the delay only applies once the current delay has elapsed.
+    client.set_admin_change_delay(&new_admin, &0);
+    assert_eq!(client.get_admin_change_delay(), delay_secs);
+    assert_eq!(
+        client.get_pending_admin_change_delay(),
+        Some((0, 1000 + delay_secs * 3))
+    );
+    assert!(client
+        .try_set_admin(&new_admin, &Address::generate(&env), &true)
+        .is_err());
+    env.ledger().set_timestamp(1000 + delay_secs * 3);
+    client.set_admin_change_delay(&new_admin, &0);
+    assert_eq!(client.get_admin_change_delay(), 0);
+    assert_eq!(client.get_pending_admin_change_delay(), None);
+}
+
+#[test]
+#[should_panic(expected = "Admin change delay not elapsed")]
+fn test_admin_change_before_delay() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let new_admin: Address = Address::generate(&env);
+    let delay_secs: u64 = 3600;
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    client.set_admin_change_delay(&admin, &delay_secs);
+
+    env.ledger().set_timestamp(1000);
+    client.propose_admin_change(&admin, &new_admin, &true);
+
+    env.ledger().set_timestamp(1000 + delay_secs - 1);
+
+    // Panics because the admin change delay has not elapsed yet.
+    client.execute_admin_change(&admin);
+}
+
+#[test]
+fn test_cancel_admin_change() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let new_admin: Address = Address::generate(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    client.set_admin_change_delay(&admin, &3600);
+    client.propose_admin_change(&admin, &new_admin, &true);
+
+    // Non-admins cannot cancel the pending admin change.
+    assert!(client.try_cancel_admin_change(&new_admin).is_err());
+
+    client.cancel_admin_change(&admin);
+
+    assert!(client.try_get_pending_admin_change().is_err());
+    assert!(client.try_execute_admin_change(&admin).is_err());
+    assert!(!client.is_admin(&new_admin));
+
+    // A new admin change can be proposed once cancelled.
+    client.propose_admin_change(&admin, &new_admin, &true);
+    assert_eq!(client.get_pending_admin_change().admin, new_admin);
+}
+
+#[test]
+fn test_health() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = TokenVestingManagerClient::new(&env, &contract_id);
+
+    assert!(!client.health());
+
+    let admin: Address = Address::generate(&env);
+    let (_, _, token_address) = deploy_token_helper(&env);
+    client.init(&admin, &token_address, &None, &0);
+
+    assert!(client.health());
+}
+
+#[test]
+fn test_get_recipient_claimed_total() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let other_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for vesting_recipient in [&recipient, &recipient, &other_recipient] {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            vesting_recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    let fresh_claimed_sum = |recipient: &Address| -> i128 {
+        client
+            .get_all_recipient_vestings(recipient)
+            .iter()
+            .map(|vesting_id| client.get_vesting_info(&vesting_id).claimed_amount)
+            .sum()
+    };
+
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 0);
+
+    env.ledger().set_timestamp(start_timestamp + 200);
+    client.claim(&recipient, &vesting_ids.get(0).unwrap());
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 300);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim_all(&recipient);
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 1200);
+    assert_eq!(
+        client.get_recipient_claimed_total(&recipient),
+        fresh_claimed_sum(&recipient)
+    );
+
+    // Reconciliations move no tokens, so they do not change the total paid out.
+    token_admin_client.mint(&client.address, &50);
+    client.admin_set_claimed(&admin, &vesting_ids.get(1).unwrap(), &550);
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 1200);
+
+    // Claims of other recipients are tracked separately.
+    client.claim(&other_recipient, &vesting_ids.get(2).unwrap());
+    assert_eq!(client.get_recipient_claimed_total(&other_recipient), 600);
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 1200);
+}
+
+#[test]
+fn test_revoke_vesting_refund_to() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let destination: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Refunds are withdrawals, unavailable while withdrawals are delayed.
+    client.set_withdraw_delay(&admin, &100);
+    assert!(client
+        .try_revoke_vesting_refund_to(&admin, &vesting_id, &destination)
+        .is_err());
+    client.set_withdraw_delay(&admin, &0);
+
+    env.ledger().set_timestamp(start_timestamp + 300);
+    client.set_withdraw_delay(&admin, &0);
+
+    let not_admin: Address = Address::generate(&env);
+    assert!(client
+        .try_revoke_vesting_refund_to(&not_admin, &vesting_id, &destination)
+        .is_err());
+
+    client.revoke_vesting_refund_to(&admin, &vesting_id, &destination);
+
+    let refund_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && topics == &vec![&env, symbol_short!("REFUNDED").into_val(&env)]
+        })
+        .count();
+    assert_eq!(refund_events, 1);
+
+    // The destination receives exactly the unvested amount.
+    assert_eq!(token_client.balance(&destination), 700);
+    assert_eq!(token_client.balance(&client.address), 300);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 300);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 0);
+
+    // The recipient keeps the vested but unclaimed tokens.
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 300);
+
+    assert!(client
+        .try_revoke_vesting_refund_to(&admin, &vesting_id, &destination)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Withdrawal requires approval")]
+fn test_revoke_vesting_refund_to_with_approvals() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let second_admin: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &3600);
+
+    // Panics because withdrawals require the approval of two admins.
+    client.revoke_vesting_refund_to(&admin, &vesting_id, &admin);
+}
+
+#[test]
+fn test_split_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let new_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 300;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 600);
+    client.claim(&recipient, &vesting_id);
+    let claimed_amount = client.get_vesting_info(&vesting_id).claimed_amount;
+
+    assert!(client
+        .try_split_vesting(&admin, &vesting_id, &new_recipient, &10000)
+        .is_err());
+    let not_admin: Address = Address::generate(&env);
+    assert!(client
+        .try_split_vesting(&not_admin, &vesting_id, &new_recipient, &2500)
+        .is_err());
+
+    // Moving more unvested tokens than the revoke approval threshold is rejected.
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_revoke_approval_threshold(&admin, &100);
+    assert!(client
+        .try_split_vesting(&admin, &vesting_id, &new_recipient, &2500)
+        .is_err());
+    client.set_revoke_approval_threshold(&admin, &0);
+    client.set_revoke_approval_threshold(&second_admin, &0);
+
+    client.set_vesting_paused(&admin, &vesting_id, &true);
+
+    let new_vesting_id: u64 =
+        client.split_vesting(&second_admin, &vesting_id, &new_recipient, &2500);
+
+    // The creation event of the new vesting carries its final claimed amount.
+    let mut created_vestings: Vec<Vesting> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        if contract_address == client.address
+            && topics
+                == vec![
+                    &env,
+                    symbol_short!("VCREATED").into_val(&env),
+                    new_recipient.into_val(&env),
+                    new_vesting_id.into_val(&env),
+                ]
+        {
+            let (_, _, vesting, _) =
+                <(u64, Address, Vesting, i128)>::try_from_val(&env, &data).unwrap();
+            created_vestings.push_back(vesting);
+        }
+    }
+    assert_eq!(
+        created_vestings,
+        vec![&env, client.get_vesting_info(&new_vesting_id)]
+    );
+    assert_eq!(
+        created_vestings.get(0).unwrap().claimed_amount,
+        claimed_amount / 4
+    );
+
+    // The new vesting keeps the funder and the flags of the original one.
+    assert_eq!(client.get_vesting_funder(&new_vesting_id), admin);
+    assert!(client.is_vesting_paused(&new_vesting_id));
+    client.set_vesting_paused(&admin, &vesting_id, &false);
+    client.set_vesting_paused(&admin, &new_vesting_id, &false);
+
+    let vesting = client.get_vesting_info(&vesting_id);
+    let new_vesting = client.get_vesting_info(&new_vesting_id);
+
+    // The amounts of the two schedules sum to the original ones.
+    assert_eq!(new_vesting.recipient, new_recipient);
+    assert_eq!(new_vesting.initial_unlock, 25);
+    assert_eq!(new_vesting.cliff_amount, 75);
+    assert_eq!(new_vesting.linear_vest_amount, 250);
+    assert_eq!(
+        vesting.initial_unlock + new_vesting.initial_unlock,
+        initial_unlock
+    );
+    assert_eq!(
+        vesting.cliff_amount + new_vesting.cliff_amount,
+        cliff_amount
+    );
+    assert_eq!(
+        vesting.linear_vest_amount + new_vesting.linear_vest_amount,
+        linear_vest_amount
+    );
+    assert_eq!(new_vesting.claimed_amount, claimed_amount / 4);
+    assert_eq!(
+        vesting.claimed_amount + new_vesting.claimed_amount,
+        claimed_amount
+    );
+
+    // The timing is identical.
+    assert_eq!(new_vesting.start_timestamp, vesting.start_timestamp);
+    assert_eq!(new_vesting.end_timestamp, vesting.end_timestamp);
+    assert_eq!(new_vesting.timelock, vesting.timelock);
+    assert_eq!(
+        new_vesting.cliff_release_timestamp,
+        vesting.cliff_release_timestamp
+    );
+    assert_eq!(
+        new_vesting.release_interval_secs,
+        vesting.release_interval_secs
+    );
+
+    // Nothing is claimable right after the split, and the reserved tokens are unchanged.
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(start_timestamp + 600)),
+        0
+    );
+    assert_eq!(
+        client.get_claimable_amount(&new_vesting_id, &(start_timestamp + 600)),
+        0
+    );
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount - claimed_amount
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    client.claim(&new_recipient, &new_vesting_id);
+
+    assert_eq!(
+        token_client.balance(&recipient) + token_client.balance(&new_recipient),
+        total_expected_amount
+    );
+    assert_eq!(
+        token_client.balance(&new_recipient),
+        25 + 75 + 250 - claimed_amount / 4
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 0);
+}
+
+#[test]
+fn test_max_future_start_secs() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let now: u64 = 1_700_000_000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    env.ledger().set_timestamp(now);
+
+    assert_eq!(client.get_max_future_start_secs(), 0);
+    client.set_max_future_start_secs(&admin, &(10 * 365 * 24 * 3600));
+    assert_eq!(client.get_max_future_start_secs(), 10 * 365 * 24 * 3600);
+
+    // A start given in milliseconds is rejected.
+    let absurd_start_timestamp: u64 = now * 1000;
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &recipient,
+            &absurd_start_timestamp,
+            &(absurd_start_timestamp + 1000),
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    // A start a year ahead is accepted.
+    let start_timestamp: u64 = now + 365 * 24 * 3600;
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &(start_timestamp + 1000),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Disabling the check accepts any start.
+    client.set_max_future_start_secs(&admin, &0);
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &absurd_start_timestamp,
+        &(absurd_start_timestamp + 1000),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+fn test_create_vesting_batch_surplus() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipients = vec![&env, Address::generate(&env), Address::generate(&env)];
+    let start_timestamps = vec![&env, 1000, 2000];
+    let end_timestamps = vec![&env, 2000, 3000];
+    let timelocks = vec![&env, 0, 0];
+    let release_interval_secs = vec![&env, 10, 10];
+    let cliff_release_timestamps = vec![&env, 0, 0];
+    let initial_unlocks = vec![&env, 100, 200];
+    let cliff_amounts = vec![&env, 0, 0];
+    let linear_vest_amounts = vec![&env, 1000, 2000];
+
+    let batch_amount: i128 = 3300;
+    let surplus: i128 = 5000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(batch_amount + surplus));
+
+    // The admin over-approves the contract.
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(batch_amount + surplus),
+        &expiration_ledger,
+    );
+
+    let vesting_params = CreateVestingBatchParams {
+        recipients,
+        start_timestamps,
+        end_timestamps,
+        timelocks,
+        initial_unlocks,
+        cliff_release_timestamps,
+        cliff_amounts,
+        release_interval_secs,
+        linear_vest_amounts,
+    };
+    client.create_vesting_batch(&admin, &vesting_params);
+
+    // Only the batch amount is pulled and reserved: the surplus allowance is left unused.
+    assert_eq!(token_client.balance(&client.address), batch_amount);
+    assert_eq!(token_client.balance(&admin), surplus);
+    assert_eq!(token_client.allowance(&admin, &client.address), surplus);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), batch_amount);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 0);
+
+    // Tokens sent on top of the batch are not reserved and remain withdrawable.
+    client.deposit(&admin, &surplus);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), batch_amount);
+    assert_eq!(client.amount_to_withdraw_by_admin(), surplus);
+
+    client.withdraw_admin(&admin, &surplus);
+    assert_eq!(token_client.balance(&admin), surplus);
+    assert_eq!(token_client.balance(&client.address), batch_amount);
+    assert_eq!(
+        client.get_balance(),
+        client.get_tokens_reserved_for_vesting()
+    );
+}
+
+#[test]
+fn test_claim_recovery_blocked_while_admins_active() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let recovery: Address = Address::generate(&env);
+    let recovery_delay_secs: u64 = 2_592_000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let new_admins = vec![&env, recovery.clone()];
+
+    // Recovery is unavailable until a recovery address is set.
+    assert!(client.try_claim_recovery(&recovery, &new_admins).is_err());
+
+    // The inactivity delay must be long.
+    env.ledger().set_timestamp(1000);
+    assert!(client
+        .try_set_recovery(&admin, &Some(recovery.clone()), &86400)
+        .is_err());
+
+    client.set_recovery(&admin, &Some(recovery.clone()), &recovery_delay_secs);
+    assert_eq!(client.get_recovery_address(), Some(recovery.clone()));
+    assert_eq!(client.get_recovery_delay(), recovery_delay_secs);
+    assert_eq!(client.get_last_admin_action(), 1000);
+
+    // Blocked until the inactivity window has fully elapsed.
+    env.ledger().set_timestamp(1000 + recovery_delay_secs);
+    assert!(client.try_claim_recovery(&recovery, &new_admins).is_err());
+
+    // Any admin action restarts the inactivity window.
+    client.set_claim_cooldown(&admin, &0);
+    assert_eq!(client.get_last_admin_action(), 1000 + recovery_delay_secs);
+
+    env.ledger().set_timestamp(1000 + recovery_delay_secs + 1);
+    assert!(client.try_claim_recovery(&recovery, &new_admins).is_err());
+
+    // Only the recovery address can claim.
+    env.ledger()
+        .set_timestamp(1000 + recovery_delay_secs * 2 + 1);
+    assert!(client
+        .try_claim_recovery(&Address::generate(&env), &new_admins)
+        .is_err());
+
+    // Disabling recovery blocks it entirely.
+    client.set_recovery(&admin, &None, &0);
+    env.ledger().set_timestamp(1000 + recovery_delay_secs * 4);
+    assert!(client.try_claim_recovery(&recovery, &new_admins).is_err());
+    assert!(client.is_admin(&admin));
+}
+
+#[test]
+fn test_claim_recovery_after_inactivity() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+
+    let other_admin: Address = Address::generate(&env);
+    let recovery: Address = Address::generate(&env);
+    let backup_admin: Address = Address::generate(&env);
+    let recovery_delay_secs: u64 = 2_592_000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    env.ledger().set_timestamp(1000);
+    client.set_admin(&admin, &other_admin, &true);
+    client.set_action_threshold(&admin, &2, &3600);
+
+    // Setting the recovery address needs the approvals of two admins.
+    assert!(client
+        .try_set_recovery(&admin, &Some(recovery.clone()), &recovery_delay_secs)
+        .is_err());
+    let action_hash = recovery_action_hash(
+        &env,
+        admin.clone(),
+        Some(recovery.clone()),
+        recovery_delay_secs,
+    );
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&other_admin, &action_hash);
+    client.set_recovery(&admin, &Some(recovery.clone()), &recovery_delay_secs);
+
+    env.ledger().set_timestamp(1000 + recovery_delay_secs + 1);
+
+    // The threshold is kept, so the new admin set must be large enough to meet it.
+    assert!(client
+        .try_claim_recovery(&recovery, &vec![&env, recovery.clone()])
+        .is_err());
+    client.claim_recovery(
+        &recovery,
+        &vec![&env, recovery.clone(), backup_admin.clone()],
+    );
+
+    assert!(client.is_admin(&recovery));
+    assert!(client.is_admin(&backup_admin));
+    assert!(!client.is_admin(&admin));
+    assert!(!client.is_admin(&other_admin));
+    assert_eq!(client.get_admins_count(), 2);
+    assert_eq!(client.get_action_threshold(), 2);
+    assert_eq!(
+        client.get_last_admin_action(),
+        1000 + recovery_delay_secs + 1
+    );
+
+    // The recovered admins can act immediately.
+    let action_hash = set_admin_action_hash(&env, recovery.clone(), admin.clone(), true);
+    client.propose_action(&recovery, &action_hash);
+    client.propose_action(&backup_admin, &action_hash);
+    client.set_admin(&recovery, &admin, &true);
+    assert!(client.is_admin(&admin));
+}
+
+#[test]
+#[should_panic(expected = "Recipient is an admin")]
+fn test_block_admin_recipients_rejects_admin_recipient() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let other_admin: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    client.set_admin(&admin, &other_admin, &true);
+
+    // Admins can be recipients while the flag is off.
+    client.create_vesting(
+        &admin,
+        &other_admin,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    client.set_block_admin_recipients(&admin, &true);
+    assert!(client.is_admin_recipients_blocked());
+
+    // Panics because the recipient is an admin.
+    client.create_vesting(
+        &admin,
+        &other_admin,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Admin is a recipient")]
+fn test_block_admin_recipients_rejects_recipient_admin() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    client.set_block_admin_recipients(&admin, &true);
+
+    // Replacing the admin set cannot bypass the block either.
+    assert!(client
+        .try_replace_admins(&admin, &vec![&env, admin.clone(), recipient.clone()])
+        .is_err());
+
+    // Panics because the new admin is a recipient.
+    client.set_admin(&admin, &recipient, &true);
+}
+
+#[test]
+fn test_claim_all_events_carry_running_reserved() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 2),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..2 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &10,
+            &linear_vest_amount,
+        ));
+    }
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim_all(&recipient);
+
+    let mut reserved_values: Vec<i128> = Vec::new(&env);
+    for (contract_address, topics, data) in env.events().all().iter() {
+        for vesting_id in vesting_ids.iter() {
+            if contract_address == client.address
+                && topics
+                    == vec![
+                        &env,
+                        symbol_short!("CLAIMED").into_val(&env),
+                        recipient.into_val(&env),
+                        vesting_id.into_val(&env),
+                    ]
+            {
+                let (_, _, _, reserved_tokens) =
+                    <(u64, Address, i128, i128)>::try_from_val(&env, &data).unwrap();
+                reserved_values.push_back(reserved_tokens);
+            }
+        }
+    }
+
+    // Each event carries the reserved total right after its own claim.
+    assert_eq!(reserved_values, vec![&env, 1500, 1000]);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 1000);
+}
+
+#[test]
+fn test_claim_reverts_on_under_delivering_token() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = TokenVestingManagerClient::new(&env, &contract_id);
+    let token_address = env.register(MockFeeToken, ());
+    let token_client = MockFeeTokenClient::new(&env, &token_address);
+
+    let admin: Address = Address::generate(&env);
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    client.init(&admin, &token_address, &None, &0);
+    token_client.mint(&admin, &linear_vest_amount);
+
+    let vesting_id = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    token_client.set_fee(&1);
+
+    // Claims revert cleanly when the token moves less than the claimed amount.
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert!(client.try_claim_all(&recipient).is_err());
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), linear_vest_amount);
+    assert_eq!(token_client.balance(&client.address), linear_vest_amount);
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    // Claims go through once the token delivers the full amount.
+    token_client.set_fee(&0);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(client.get_vesting_info(&vesting_id).claimed_amount, 500);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+fn test_revoke_vesting_while_claims_paused() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.set_claims_paused(&admin, &true, &None);
+
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+
+    // No vesting can be created while paused.
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+    assert!(client
+        .try_create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &10,
+            &linear_vest_amount,
+        )
+        .is_err());
+
+    // Revocation is an emergency tool and stays available while claims are paused.
+    client.revoke_vesting(&admin, &vesting_id);
+    assert_eq!(
+        client.get_vesting_info(&vesting_id).deactivation_timestamp,
+        start_timestamp + 500
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 500);
+
+    // The vested part is still not claimable until claims are unpaused.
+    assert!(client.try_claim(&recipient, &vesting_id).is_err());
+    assert!(client.try_claim_all(&recipient).is_err());
+
+    client.set_claims_paused(&admin, &false, &None);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+fn test_transfer_all_vestings() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let new_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 3),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..3 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &10,
+            &linear_vest_amount,
+        ));
+    }
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_ids.get(0).unwrap());
+
+    // Only a recipient with vestings can transfer them, and not to themselves.
+    assert!(client
+        .try_transfer_all_vestings(&new_recipient, &recipient)
+        .is_err());
+    assert!(client
+        .try_transfer_all_vestings(&recipient, &recipient)
+        .is_err());
+
+    // A mandatory destination blocks the transfer until an admin lifts it.
+    let destination: Address = Address::generate(&env);
+    client.set_mandatory_destination(&admin, &recipient, &Some(destination));
+    assert!(client
+        .try_transfer_all_vestings(&recipient, &new_recipient)
+        .is_err());
+    client.set_mandatory_destination(&admin, &recipient, &None);
+
+    let hook_address = env.register(MockClaimHook, ());
+    client.set_claim_hook(&recipient, &Some(hook_address.clone()));
+
+    client.transfer_all_vestings(&recipient, &new_recipient);
+
+    let transferred_events = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            contract_address == &client.address
+                && vesting_ids.iter().any(|vesting_id| {
+                    topics
+                        == &vec![
+                            &env,
+                            symbol_short!("VTRANSFER").into_val(&env),
+                            new_recipient.into_val(&env),
+                            vesting_id.into_val(&env),
+                        ]
+                })
+        })
+        .count();
+    assert_eq!(transferred_events, 3);
+
+    assert!(!client.is_recipient(&recipient));
+    assert!(client.get_all_recipient_vestings(&recipient).is_empty());
+    assert_eq!(
+        client.get_all_recipient_vestings(&new_recipient),
+        vesting_ids
+    );
+    assert_eq!(
+        client.get_all_recipients(),
+        vec![&env, new_recipient.clone()]
+    );
+    for vesting_id in vesting_ids.iter() {
+        assert_eq!(
+            client.get_vesting_info(&vesting_id).recipient,
+            new_recipient
+        );
+    }
+    // The claimed total follows the moved vestings.
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 0);
+    assert_eq!(client.get_recipient_claimed_total(&new_recipient), 500);
+    // The claim hook moves along.
+    assert_eq!(client.get_claim_hook(&recipient), None);
+    assert_eq!(client.get_claim_hook(&new_recipient), Some(hook_address));
+
+    // The new recipient claims the moved vestings, the former one cannot.
+    assert!(client
+        .try_claim(&recipient, &vesting_ids.get(1).unwrap())
+        .is_err());
+    client.claim(&new_recipient, &vesting_ids.get(1).unwrap());
+    assert_eq!(token_client.balance(&new_recipient), 500);
+
+    let claimed_sum: i128 = vesting_ids
+        .iter()
+        .map(|vesting_id| client.get_vesting_info(&vesting_id).claimed_amount)
+        .sum();
+    assert_eq!(client.get_recipient_claimed_total(&new_recipient), 1000);
+    assert_eq!(
+        client.get_recipient_claimed_total(&new_recipient),
+        claimed_sum
+    );
+    assert_eq!(client.get_recipient_claimed_total(&recipient), 0);
+}
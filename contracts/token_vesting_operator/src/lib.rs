@@ -0,0 +1,308 @@
+#![no_std]
+// Entrypoint arguments are the contract interface.
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec,
+};
+use token_vesting_common::Vesting;
+
+// Prefix of the entries mapping a manager and an idempotency key to the vesting ID it created.
+const IDEMPOTENCY_KEYS: Symbol = symbol_short!("IDEMKEYS");
+
+// Minimum TTL before extending the storage lifetime: 20 days in 5 seconds ledger time
+const LIFETIME_THRESHOLD: u32 = 345_600;
+// Extension amount for the storage lifetime: 30 days in 5 seconds ledger time
+const EXTENSION_AMOUNT: u32 = 518_400;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateVestingParams {
+    pub recipient: Address,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub timelock: u64,
+    pub initial_unlock: i128,
+    pub cliff_release_timestamp: u64,
+    pub cliff_amount: i128,
+    pub release_interval_secs: u64,
+    pub linear_vest_amount: i128,
+}
+
+/// Functions of the Token Vesting Manager contract used by the operator.
+#[contractclient(name = "TokenVestingManagerClient")]
+pub trait TokenVestingManagerInterface {
+    fn is_admin(env: Env, address: Address) -> bool;
+    fn get_vesting_info(env: Env, vesting_id: u64) -> Vesting;
+    fn calculate_ramped_vested_amount(
+        env: Env,
+        vesting: Vesting,
+        reference_timestamp: u64,
+        cliff_ramp_secs: u64,
+    ) -> i128;
+    fn get_all_recipient_vestings(env: Env, recipient: Address) -> Vec<u64>;
+    fn is_irrevocable(env: Env, vesting_id: u64) -> bool;
+    fn get_revoke_approval_threshold(env: Env) -> i128;
+    fn get_unvested_amount(env: Env, vesting_id: u64, reference_timestamp: u64) -> i128;
+    fn revoke_vesting(env: Env, caller: Address, vesting_id: u64);
+    fn create_vesting(
+        env: Env,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        release_interval_secs: u64,
+        linear_vest_amount: i128,
+    ) -> u64;
+    fn get_cliff_ramp(env: Env, vesting_id: u64) -> u64;
+    fn get_default_release_interval(env: Env) -> u64;
+    fn withdraw_other_token(env: Env, caller: Address, other_token_address: Address);
+    fn set_cliff_ramp(env: Env, caller: Address, vesting_id: u64, cliff_ramp_secs: u64);
+}
+
+/// Contract running multi-step admin operations over a Token Vesting Manager contract, which
+/// are kept out of the manager to bound its Wasm size.
+/// Every operation takes the address of the manager to act on as first argument. The operator
+/// holds no privilege and only stores the idempotency keys it was given: each step is a call of
+/// the manager made on behalf of `caller`, which must be an admin of the manager and authorize
+/// those calls.
+#[contract]
+pub struct TokenVestingOperator;
+
+#[contractimpl]
+impl TokenVestingOperator {
+    /// Revokes all the active vestings of a recipient, skipping the revoked and fully claimed ones.
+    /// Irrevocable vestings and vestings whose revocation requires an approval are skipped too,
+    /// and their IDs are returned.
+    pub fn revoke_all_for_recipient(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        recipient: Address,
+    ) -> Vec<u64> {
+        // Access control check, the manager checks that the caller is an admin.
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+        let revoke_approval_threshold = client.get_revoke_approval_threshold();
+
+        let mut skipped_vesting_ids: Vec<u64> = Vec::new(&env);
+        for vesting_id in client.get_all_recipient_vestings(&recipient).iter() {
+            let vesting = client.get_vesting_info(&vesting_id);
+
+            let final_vest_amount = client.calculate_ramped_vested_amount(
+                &vesting,
+                &vesting.end_timestamp,
+                &client.get_cliff_ramp(&vesting_id),
+            );
+
+            if vesting.deactivation_timestamp != 0 || final_vest_amount == vesting.claimed_amount {
+                continue;
+            }
+
+            if client.is_irrevocable(&vesting_id)
+                || (revoke_approval_threshold != 0
+                    && client.get_unvested_amount(&vesting_id, &env.ledger().timestamp())
+                        > revoke_approval_threshold)
+            {
+                skipped_vesting_ids.push_back(vesting_id);
+                continue;
+            }
+
+            client.revoke_vesting(&caller, &vesting_id);
+        }
+
+        skipped_vesting_ids
+    }
+
+    /// Withdraws the full balance of each of the given other tokens accidentally sent to the
+    /// manager. Panics if the vesting token is listed.
+    pub fn withdraw_other_tokens(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        tokens: Vec<Address>,
+    ) {
+        // Access control check, the manager checks that the caller is an admin.
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        for other_token_address in tokens.iter() {
+            client.withdraw_other_token(&caller, &other_token_address);
+        }
+    }
+
+    /// Creates a vesting schedule for a recipient and returns both the vesting ID and the stored vesting.
+    pub fn create_vesting_returning(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        create_vesting_params: CreateVestingParams,
+    ) -> (u64, Vesting) {
+        // Access control check, the manager checks that the caller is an admin.
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting_id = Self::create_vesting(&client, &caller, &create_vesting_params);
+
+        (vesting_id, client.get_vesting_info(&vesting_id))
+    }
+
+    /// Creates a vesting schedule for a recipient using the default release interval of the
+    /// manager and returns a vesting ID.
+    pub fn create_vesting_default_interval(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        recipient: Address,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        timelock: u64,
+        initial_unlock: i128,
+        cliff_release_timestamp: u64,
+        cliff_amount: i128,
+        linear_vest_amount: i128,
+    ) -> u64 {
+        // Access control check, the manager checks that the caller is an admin.
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let release_interval_secs = client.get_default_release_interval();
+        assert!(
+            release_interval_secs != 0,
+            "Default release interval not set"
+        );
+
+        client.create_vesting(
+            &caller,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        )
+    }
+
+    /// Creates a vesting for a new recipient with the same parameters as a template vesting,
+    /// its timestamps being shifted relative to the new start timestamp. Returns the vesting ID.
+    /// The cliff ramp of the template is kept, so a ramped template can only be cloned while the
+    /// cliff of the clone is still ahead.
+    pub fn clone_vesting(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        template_vesting_id: u64,
+        new_recipient: Address,
+        new_start_timestamp: u64,
+    ) -> u64 {
+        // Access control check, the manager checks that the caller is an admin.
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let template = client.get_vesting_info(&template_vesting_id);
+
+        let shift = |timestamp: u64| -> u64 {
+            if timestamp <= template.start_timestamp {
+                0
+            } else {
+                new_start_timestamp + (timestamp - template.start_timestamp)
+            }
+        };
+
+        let cliff_release_timestamp = if template.cliff_release_timestamp == 0 {
+            0
+        } else {
+            new_start_timestamp + (template.cliff_release_timestamp - template.start_timestamp)
+        };
+
+        let cloned_vesting_id = client.create_vesting(
+            &caller,
+            &new_recipient,
+            &new_start_timestamp,
+            &shift(template.end_timestamp),
+            &shift(template.timelock),
+            &template.initial_unlock,
+            &cliff_release_timestamp,
+            &template.cliff_amount,
+            &template.release_interval_secs,
+            &template.linear_vest_amount,
+        );
+
+        let cliff_ramp_secs = client.get_cliff_ramp(&template_vesting_id);
+        if cliff_ramp_secs != 0 {
+            client.set_cliff_ramp(&caller, &cloned_vesting_id, &cliff_ramp_secs);
+        }
+
+        cloned_vesting_id
+    }
+
+    /// Creates a vesting schedule for a recipient once per manager and idempotency key, and
+    /// returns its vesting ID. Repeating a key returns the vesting ID it created, without creating
+    /// or funding another vesting.
+    pub fn create_vesting_idempotent(
+        env: Env,
+        manager: Address,
+        caller: Address,
+        idempotency_key: BytesN<32>,
+        create_vesting_params: CreateVestingParams,
+    ) -> u64 {
+        // Access control check
+        caller.require_auth();
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+        assert!(client.is_admin(&caller), "Not an admin");
+
+        // Each key has its own entry, so the cost does not grow with the number of keys used.
+        let idempotency_entry = (IDEMPOTENCY_KEYS, manager, idempotency_key);
+        if let Some(vesting_id) = env.storage().persistent().get(&idempotency_entry) {
+            return vesting_id;
+        }
+
+        let vesting_id = Self::create_vesting(&client, &caller, &create_vesting_params);
+
+        env.storage()
+            .persistent()
+            .set(&idempotency_entry, &vesting_id);
+        env.storage().persistent().extend_ttl(
+            &idempotency_entry,
+            LIFETIME_THRESHOLD,
+            EXTENSION_AMOUNT,
+        );
+
+        vesting_id
+    }
+
+    /// Creates a vesting schedule on the manager from its parameters and returns its vesting ID.
+    fn create_vesting(
+        client: &TokenVestingManagerClient,
+        caller: &Address,
+        create_vesting_params: &CreateVestingParams,
+    ) -> u64 {
+        client.create_vesting(
+            caller,
+            &create_vesting_params.recipient,
+            &create_vesting_params.start_timestamp,
+            &create_vesting_params.end_timestamp,
+            &create_vesting_params.timelock,
+            &create_vesting_params.initial_unlock,
+            &create_vesting_params.cliff_release_timestamp,
+            &create_vesting_params.cliff_amount,
+            &create_vesting_params.release_interval_secs,
+            &create_vesting_params.linear_vest_amount,
+        )
+    }
+}
+
+mod test;
@@ -0,0 +1,685 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{
+    symbol_short, testutils::Address as TestAddress, testutils::Events, testutils::Ledger, vec,
+    Env, IntoVal, TryFromVal,
+};
+use token_vesting_manager::{TokenVestingManager, TokenVestingManagerClient as ManagerClient};
+
+fn deploy_manager_helper(
+    env: &Env,
+) -> (
+    ManagerClient<'_>,
+    Address,
+    TokenClient<'_>,
+    StellarAssetClient<'_>,
+    Address,
+) {
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = ManagerClient::new(env, &contract_id);
+
+    let admin: Address = Address::generate(env);
+    let (token_client, token_admin_client, token_address) = deploy_token_helper(env);
+    client.init(&admin, &token_address, &None, &0);
+
+    (
+        client,
+        admin,
+        token_client,
+        token_admin_client,
+        token_address,
+    )
+}
+
+fn deploy_token_helper(env: &Env) -> (TokenClient<'_>, StellarAssetClient<'_>, Address) {
+    let token_admin = Address::generate(env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_admin_client: StellarAssetClient<'_> =
+        StellarAssetClient::new(env, &token_contract_id.address());
+    let token_client = TokenClient::new(env, &token_contract_id.address());
+
+    (
+        token_client,
+        token_admin_client,
+        token_contract_id.address(),
+    )
+}
+
+fn deploy_operator_helper(env: &Env) -> TokenVestingOperatorClient<'_> {
+    let contract_id = env.register(TokenVestingOperator, ());
+
+    TokenVestingOperatorClient::new(env, &contract_id)
+}
+
+#[test]
+fn test_revoke_all_for_recipient() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = 3 * linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    for _ in 0..3 {
+        client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        );
+    }
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+
+    // An already revoked vesting is skipped.
+    client.revoke_vesting(&admin, &0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 2500);
+
+    assert_eq!(
+        operator.revoke_all_for_recipient(&client.address, &admin, &recipient),
+        Vec::new(&env)
+    );
+
+    let revoked_count = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract_address, topics, _)| {
+            *contract_address == client.address
+                && (1..3).any(|vesting_id: u64| {
+                    *topics
+                        == vec![
+                            &env,
+                            symbol_short!("VREVOKED").into_val(&env),
+                            recipient.into_val(&env),
+                            vesting_id.into_val(&env),
+                        ]
+                })
+        })
+        .count();
+    assert_eq!(revoked_count, 2);
+
+    for vesting_id in 0..3 {
+        assert_eq!(
+            client.get_vesting_info(&vesting_id).deactivation_timestamp,
+            start_timestamp + 500
+        );
+    }
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 1500);
+    assert_eq!(client.amount_to_withdraw_by_admin(), 1500);
+}
+
+#[test]
+fn test_revoke_all_for_recipient_skips_protected() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let linear_vest_amount: i128 = 1000;
+    let large_linear_vest_amount: i128 = 3000;
+    let total_expected_amount: i128 = linear_vest_amount * 2 + large_linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+    client.create_irrevocable_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &large_linear_vest_amount,
+    );
+    client.set_revoke_approval_threshold(&admin, &1000);
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+
+    // The irrevocable vesting and the one needing a revoke approval are skipped and returned.
+    assert_eq!(
+        operator.revoke_all_for_recipient(&client.address, &admin, &recipient),
+        vec![&env, 1, 2]
+    );
+    assert_eq!(
+        client.get_vesting_info(&0).deactivation_timestamp,
+        start_timestamp + 500
+    );
+    assert_eq!(client.get_vesting_info(&1).deactivation_timestamp, 0);
+    assert_eq!(client.get_vesting_info(&2).deactivation_timestamp, 0);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), 4500);
+}
+
+#[test]
+fn test_create_vesting_returning() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let (vesting_id, vesting) = operator.create_vesting_returning(
+        &client.address,
+        &admin,
+        &CreateVestingParams {
+            recipient: recipient.clone(),
+            start_timestamp,
+            end_timestamp,
+            timelock,
+            initial_unlock,
+            cliff_release_timestamp,
+            cliff_amount,
+            release_interval_secs,
+            linear_vest_amount,
+        },
+    );
+
+    assert_eq!(
+        vesting,
+        TokenVestingManagerClient::new(&env, &client.address).get_vesting_info(&vesting_id)
+    );
+    assert_eq!(vesting.recipient, recipient);
+    assert_eq!(vesting.claimed_amount, 0);
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount
+    );
+}
+
+#[test]
+fn test_clone_vesting() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = start_timestamp + 100;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let template_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let new_recipient: Address = Address::generate(&env);
+    let new_start_timestamp: u64 = 5000;
+    let cloned_vesting_id: u64 = operator.clone_vesting(
+        &client.address,
+        &admin,
+        &template_vesting_id,
+        &new_recipient,
+        &new_start_timestamp,
+    );
+
+    let template = client.get_vesting_info(&template_vesting_id);
+    let cloned = client.get_vesting_info(&cloned_vesting_id);
+
+    assert_eq!(cloned.recipient, new_recipient);
+    assert_eq!(cloned.start_timestamp, new_start_timestamp);
+    assert_eq!(
+        cloned.end_timestamp - cloned.start_timestamp,
+        template.end_timestamp - template.start_timestamp
+    );
+    assert_eq!(
+        cloned.cliff_release_timestamp - cloned.start_timestamp,
+        template.cliff_release_timestamp - template.start_timestamp
+    );
+    assert_eq!(
+        cloned.timelock - cloned.start_timestamp,
+        template.timelock - template.start_timestamp
+    );
+    assert_eq!(cloned.release_interval_secs, template.release_interval_secs);
+    assert_eq!(cloned.initial_unlock, template.initial_unlock);
+    assert_eq!(cloned.cliff_amount, template.cliff_amount);
+    assert_eq!(cloned.linear_vest_amount, template.linear_vest_amount);
+    assert_eq!(cloned.claimed_amount, 0);
+    assert_eq!(cloned.deactivation_timestamp, 0);
+
+    // The clone is funded like a new vesting.
+    assert_eq!(token_client.balance(&admin), total_expected_amount);
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount * 2
+    );
+
+    // Cloning keeps the cliff ramp.
+    let cliff_ramp_secs: u64 = 100;
+    client.set_cliff_ramp(&admin, &template_vesting_id, &cliff_ramp_secs);
+    let ramped_clone_id: u64 = operator.clone_vesting(
+        &client.address,
+        &admin,
+        &template_vesting_id,
+        &new_recipient,
+        &new_start_timestamp,
+    );
+    assert_eq!(client.get_cliff_ramp(&ramped_clone_id), cliff_ramp_secs);
+    assert_eq!(
+        client.calculate_ramped_vested_amount(
+            &client.get_vesting_info(&ramped_clone_id),
+            &(new_start_timestamp + 500 + cliff_ramp_secs / 2),
+            &client.get_cliff_ramp(&ramped_clone_id)
+        ),
+        client.calculate_ramped_vested_amount(
+            &client.get_vesting_info(&template_vesting_id),
+            &(start_timestamp + 500 + cliff_ramp_secs / 2),
+            &cliff_ramp_secs
+        )
+    );
+}
+
+#[test]
+fn test_create_vesting_default_interval() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.set_default_release_interval(&admin, &100);
+    assert_eq!(client.get_default_release_interval(), 100);
+
+    let vesting_id: u64 = operator.create_vesting_default_interval(
+        &client.address,
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(
+        client.get_vesting_info(&vesting_id).release_interval_secs,
+        100
+    );
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(start_timestamp + 150)),
+        1100
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_create_vesting_default_interval_not_divisible() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.set_default_release_interval(&admin, &300);
+
+    // Panics because the vesting duration is not a multiple of the default release interval.
+    operator.create_vesting_default_interval(
+        &client.address,
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &linear_vest_amount,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_create_vesting_default_interval_not_set() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    // Panics because no default release interval is set.
+    operator.create_vesting_default_interval(
+        &client.address,
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &0,
+        &0,
+        &0,
+        &1000,
+    );
+}
+
+#[test]
+fn test_withdraw_other_tokens() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, token_address) =
+        deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+    let (first_token_client, first_token_admin_client, first_token_address) =
+        deploy_token_helper(&env);
+    let (second_token_client, second_token_admin_client, second_token_address) =
+        deploy_token_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+    first_token_admin_client.mint(&client.address, &100);
+    second_token_admin_client.mint(&client.address, &250);
+    token_admin_client.mint(&client.address, &1000);
+
+    operator.withdraw_other_tokens(
+        &client.address,
+        &admin,
+        &vec![
+            &env,
+            first_token_address.clone(),
+            second_token_address.clone(),
+        ],
+    );
+
+    let withdrawn_amounts: Vec<i128> = {
+        let mut amounts: Vec<i128> = Vec::new(&env);
+        for (contract_address, topics, data) in env.events().all().iter() {
+            if contract_address == client.address
+                && topics == vec![&env, symbol_short!("WITHOTHER").into_val(&env)]
+            {
+                let (_, amount) = <(Address, i128)>::try_from_val(&env, &data).unwrap();
+                amounts.push_back(amount);
+            }
+        }
+        amounts
+    };
+    assert_eq!(withdrawn_amounts, vec![&env, 100, 250]);
+
+    assert_eq!(first_token_client.balance(&admin), 100);
+    assert_eq!(second_token_client.balance(&admin), 250);
+    assert_eq!(first_token_client.balance(&client.address), 0);
+    assert_eq!(second_token_client.balance(&client.address), 0);
+
+    // Listing the vesting token rejects the whole sweep.
+    first_token_admin_client.mint(&client.address, &100);
+    assert!(operator
+        .try_withdraw_other_tokens(
+            &client.address,
+            &admin,
+            &vec![&env, first_token_address.clone(), token_address.clone()],
+        )
+        .is_err());
+    assert_eq!(first_token_client.balance(&client.address), 100);
+    assert_eq!(token_client.balance(&client.address), 1000);
+}
+
+#[test]
+fn test_create_vesting_idempotent() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let operator = deploy_operator_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    let create_vesting_params = CreateVestingParams {
+        recipient: recipient.clone(),
+        start_timestamp: 1000,
+        end_timestamp: 2000,
+        timelock: 0,
+        initial_unlock: 0,
+        cliff_release_timestamp: 0,
+        cliff_amount: 0,
+        release_interval_secs: 10,
+        linear_vest_amount,
+    };
+    let idempotency_key = BytesN::from_array(&env, &[1; 32]);
+    let other_idempotency_key = BytesN::from_array(&env, &[2; 32]);
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 3),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = operator.create_vesting_idempotent(
+        &client.address,
+        &admin,
+        &idempotency_key,
+        &create_vesting_params,
+    );
+    assert_eq!(token_client.balance(&client.address), linear_vest_amount);
+
+    // A repeated key returns the same vesting without a second token transfer.
+    assert_eq!(
+        operator.create_vesting_idempotent(
+            &client.address,
+            &admin,
+            &idempotency_key,
+            &create_vesting_params
+        ),
+        vesting_id
+    );
+    assert_eq!(token_client.balance(&client.address), linear_vest_amount);
+    assert_eq!(client.get_tokens_reserved_for_vesting(), linear_vest_amount);
+    assert_eq!(
+        client.get_all_recipient_vestings(&recipient),
+        vec![&env, vesting_id]
+    );
+
+    // Another key creates another vesting.
+    let other_vesting_id: u64 = operator.create_vesting_idempotent(
+        &client.address,
+        &admin,
+        &other_idempotency_key,
+        &create_vesting_params,
+    );
+    assert_ne!(other_vesting_id, vesting_id);
+    assert_eq!(
+        token_client.balance(&client.address),
+        linear_vest_amount * 2
+    );
+
+    // Each key is stored in its own entry rather than in one shared map.
+    env.as_contract(&operator.address, || {
+        let storage = env.storage().persistent();
+        assert!(!storage.has(&IDEMPOTENCY_KEYS));
+        assert_eq!(
+            storage.get(&(
+                IDEMPOTENCY_KEYS,
+                client.address.clone(),
+                other_idempotency_key.clone()
+            )),
+            Some(other_vesting_id)
+        );
+    });
+
+    // Keys are scoped to the manager, so the same key creates a vesting on another manager.
+    let (other_client, other_admin, other_token_client, other_token_admin_client, _) =
+        deploy_manager_helper(&env);
+    other_token_admin_client.mint(&other_admin, &linear_vest_amount);
+    other_token_client.approve(
+        &other_admin,
+        &other_client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+    operator.create_vesting_idempotent(
+        &other_client.address,
+        &other_admin,
+        &idempotency_key,
+        &create_vesting_params,
+    );
+    assert_eq!(
+        other_client.get_all_recipient_vestings(&recipient),
+        vec![&env, 0]
+    );
+
+    let not_admin: Address = Address::generate(&env);
+    assert!(operator
+        .try_create_vesting_idempotent(
+            &client.address,
+            &not_admin,
+            &idempotency_key,
+            &create_vesting_params
+        )
+        .is_err());
+}
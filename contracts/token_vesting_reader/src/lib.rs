@@ -0,0 +1,916 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, token::TokenClient,
+    xdr::ToXdr, Address, BytesN, Env, String, Symbol, Vec,
+};
+use token_vesting_common::{
+    recovery_action_hash, replace_admins_action_hash, set_admin_action_hash, threshold_action_hash,
+    upgrade_action_hash, withdraw_action_hash, Vesting,
+};
+
+// Schedule type code of the linear vestings of the Token Vesting Manager contract.
+const SCHEDULE_TYPE_LINEAR: u32 = 0;
+
+// Status code of a vesting that is neither revoked nor fully claimed.
+const STATUS_ACTIVE: u32 = 0;
+// Status code of a revoked vesting.
+const STATUS_REVOKED: u32 = 1;
+// Status code of a vesting whose full amount has been claimed.
+const STATUS_DRAINED: u32 = 2;
+
+// Maximum number of entries a single call of an aggregate view can be asked to scan.
+const MAX_SCAN: u32 = 25;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingStatus {
+    pub status: u32,
+    pub vested_amount: i128,
+    pub claimed_amount: i128,
+    pub claimable_amount: i128,
+}
+
+/// Read-only functions of the Token Vesting Manager contract used by the reader.
+#[contractclient(name = "TokenVestingManagerClient")]
+pub trait TokenVestingManagerInterface {
+    fn get_vesting_info(env: Env, vesting_id: u64) -> Vesting;
+    fn get_vesting_info_or_default(env: Env, vesting_id: u64) -> Option<Vesting>;
+    fn calculate_ramped_vested_amount(
+        env: Env,
+        vesting: Vesting,
+        reference_timestamp: u64,
+        cliff_ramp_secs: u64,
+    ) -> i128;
+    fn get_claimable_amount(env: Env, vesting_id: u64, reference_timestamp: u64) -> i128;
+    fn get_cliff_ramp(env: Env, vesting_id: u64) -> u64;
+    fn get_all_recipients_len(env: Env) -> u32;
+    fn get_all_recipients_sliced(env: Env, from: u32, to: u32) -> Vec<Address>;
+    fn get_all_recipient_vesting_sliced(
+        env: Env,
+        from: u32,
+        to: u32,
+        recipient: Address,
+    ) -> Vec<u64>;
+    fn get_token_address(env: Env) -> Address;
+    fn get_tokens_reserved_for_vesting(env: Env) -> i128;
+    fn get_balance(env: Env) -> i128;
+    fn amount_to_withdraw_by_admin(env: Env) -> i128;
+    fn is_ack_required(env: Env) -> bool;
+    fn get_claim_cooldown(env: Env) -> u64;
+    fn get_reclaim_grace_secs(env: Env) -> u64;
+    fn get_withdraw_delay(env: Env) -> u64;
+    fn get_admin_change_delay(env: Env) -> u64;
+    fn get_action_threshold(env: Env) -> u32;
+    fn get_revoke_approval_threshold(env: Env) -> i128;
+    fn get_recovery_address(env: Env) -> Option<Address>;
+    fn get_max_vestings_per_recipient(env: Env) -> u32;
+    fn get_min_duration_secs(env: Env) -> u64;
+    fn get_max_future_start_secs(env: Env) -> u64;
+    fn get_default_release_interval(env: Env) -> u64;
+    fn is_single_interval_allowed(env: Env) -> bool;
+    fn is_recipient_allowlist_enabled(env: Env) -> bool;
+    fn is_admin_recipients_blocked(env: Env) -> bool;
+}
+
+/// Stateless contract computing aggregate and derived views over a Token Vesting Manager
+/// contract, which are kept out of the manager to bound its Wasm size.
+/// Every view takes the address of the manager to read from as first argument.
+#[contract]
+pub struct TokenVestingReader;
+
+#[contractimpl]
+impl TokenVestingReader {
+    /// Returns true if claiming at `reference_timestamp` would bring the claimed amount of a
+    /// vesting to its final total, false if it would be partial or there is nothing to claim.
+    pub fn claim_would_complete(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+        reference_timestamp: u64,
+    ) -> bool {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+        let final_vest_amount =
+            Self::vested_amount(&client, vesting_id, &vesting, vesting.end_timestamp);
+        let claimable_amount = client.get_claimable_amount(&vesting_id, &reference_timestamp);
+
+        claimable_amount > 0 && vesting.claimed_amount + claimable_amount == final_vest_amount
+    }
+
+    /// Returns the timestamp from which the linear amount of a vesting starts vesting,
+    /// which is the cliff release timestamp if set, the start timestamp otherwise.
+    pub fn get_effective_linear_start(env: Env, manager: Address, vesting_id: u64) -> u64 {
+        let vesting = TokenVestingManagerClient::new(&env, &manager).get_vesting_info(&vesting_id);
+
+        Self::linear_start(&vesting)
+    }
+
+    /// Returns the number of release intervals over which the linear amount of a vesting vests,
+    /// from its effective linear start to its end timestamp.
+    pub fn get_interval_count(env: Env, manager: Address, vesting_id: u64) -> u64 {
+        let vesting = TokenVestingManagerClient::new(&env, &manager).get_vesting_info(&vesting_id);
+
+        (vesting.end_timestamp - Self::linear_start(&vesting)) / vesting.release_interval_secs
+    }
+
+    /// Returns the first timestamp at which any amount of a vesting becomes claimable,
+    /// considering its timelock, initial unlock, cliff, cliff ramp and linear intervals.
+    /// A vesting that never vests anything returns its end timestamp.
+    pub fn get_timelock_expiry(env: Env, manager: Address, vesting_id: u64) -> u64 {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+
+        let first_vest_timestamp = Self::first_timestamp_vesting_beyond(
+            &client,
+            &vesting,
+            client.get_cliff_ramp(&vesting_id),
+            0,
+            vesting.start_timestamp,
+        );
+
+        vesting.timelock.max(first_vest_timestamp)
+    }
+
+    /// Returns the first timestamp at or after the given one at which the vesting has a positive
+    /// claimable amount, considering its timelock, or its end timestamp if nothing is left to claim.
+    pub fn next_claimable_timestamp(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+        reference_timestamp: u64,
+    ) -> u64 {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+
+        let cliff_ramp_secs = client.get_cliff_ramp(&vesting_id);
+
+        let final_vest_amount = client.calculate_ramped_vested_amount(
+            &vesting,
+            &vesting.end_timestamp,
+            &cliff_ramp_secs,
+        );
+        if final_vest_amount == vesting.claimed_amount {
+            return vesting.end_timestamp;
+        }
+
+        vesting.timelock.max(Self::first_timestamp_vesting_beyond(
+            &client,
+            &vesting,
+            cliff_ramp_secs,
+            vesting.claimed_amount,
+            reference_timestamp,
+        ))
+    }
+
+    /// Returns the share of a vesting already claimed, in basis points of the total amount it
+    /// will ever vest, which is capped at the deactivation for a revoked vesting.
+    /// A vesting with nothing to vest counts as fully claimed.
+    pub fn get_claimed_percentage(env: Env, manager: Address, vesting_id: u64) -> u32 {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+        let final_vest_amount =
+            Self::vested_amount(&client, vesting_id, &vesting, vesting.end_timestamp);
+
+        if vesting.claimed_amount >= final_vest_amount {
+            return 10000;
+        }
+
+        // Large amounts are scaled down first to avoid overflowing.
+        let claimed_bps = match vesting.claimed_amount.checked_mul(10000) {
+            Some(scaled_claimed) => scaled_claimed / final_vest_amount,
+            None => vesting.claimed_amount / (final_vest_amount / 10000),
+        };
+
+        claimed_bps as u32
+    }
+
+    /// Simulates revoking an active vesting at the given timestamp without changing state.
+    /// Returns the amount that would be refunded to the admins and the amount the recipient keeps.
+    pub fn simulate_revoke(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+        effective_timestamp: u64,
+    ) -> (i128, i128) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+        assert!(vesting.deactivation_timestamp == 0, "Vesting not active");
+
+        // Nothing vests after the deactivation, so the amount vested at the deactivation is the
+        // amount vested at the effective timestamp.
+        let final_vest_amount =
+            Self::vested_amount(&client, vesting_id, &vesting, vesting.end_timestamp);
+        let vested_amount_at_deactivation =
+            Self::vested_amount(&client, vesting_id, &vesting, effective_timestamp);
+
+        let refund_to_admin = final_vest_amount - vested_amount_at_deactivation;
+        let recipient_keeps = vested_amount_at_deactivation - vesting.claimed_amount;
+
+        (refund_to_admin, recipient_keeps)
+    }
+
+    /// Returns the amount the recipient could claim from a vesting at the given timestamp if the
+    /// vesting had no timelock.
+    pub fn claimable_ignoring_timelock(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+        reference_timestamp: u64,
+    ) -> i128 {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+
+        Self::vested_amount(&client, vesting_id, &vesting, reference_timestamp)
+            - vesting.claimed_amount
+    }
+
+    /// Returns up to `max_points` (timestamp, cumulative vested amount) pairs of a vesting schedule,
+    /// from the start (initial unlock), through the cliff, to each linear interval boundary up to the end.
+    /// If the schedule has more points than `max_points`, they are sampled evenly, always keeping
+    /// the first and last points.
+    pub fn get_schedule_grid(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+        max_points: u32,
+    ) -> Vec<(u64, i128)> {
+        assert!(max_points >= 2, "Invalid max points");
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+        let cliff_ramp_secs = client.get_cliff_ramp(&vesting_id);
+
+        let has_cliff_point: bool = vesting.cliff_release_timestamp != 0
+            && vesting.cliff_release_timestamp != vesting.start_timestamp;
+
+        let linear_start_timestamp: u64 = Self::linear_start(&vesting);
+
+        let number_of_intervals: u64 =
+            (vesting.end_timestamp - linear_start_timestamp) / vesting.release_interval_secs;
+        let number_of_points: u64 = 1 + has_cliff_point as u64 + number_of_intervals;
+
+        let mut points: Vec<(u64, i128)> = Vec::new(&env);
+        let points_len: u64 = number_of_points.min(max_points as u64);
+
+        for i in 0..points_len {
+            let point_index: u64 = if number_of_points > max_points as u64 {
+                i * (number_of_points - 1) / (points_len - 1)
+            } else {
+                i
+            };
+
+            let timestamp: u64 = if point_index == 0 {
+                vesting.start_timestamp
+            } else if has_cliff_point && point_index == 1 {
+                vesting.cliff_release_timestamp
+            } else {
+                let interval_index: u64 = point_index - has_cliff_point as u64;
+                linear_start_timestamp + interval_index * vesting.release_interval_secs
+            };
+
+            points.push_back((
+                timestamp,
+                client.calculate_ramped_vested_amount(&vesting, &timestamp, &cliff_ramp_secs),
+            ));
+        }
+
+        points
+    }
+
+    /// Returns a hash over the schedule parameters of a vesting set at creation, plus its cliff
+    /// ramp. Claims and revocations leave it unchanged, so off-chain systems can compare it
+    /// against the hash seen at creation to detect admin edits to the schedule.
+    pub fn get_vesting_params_hash(env: Env, manager: Address, vesting_id: u64) -> BytesN<32> {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting = client.get_vesting_info(&vesting_id);
+        let cliff_ramp_secs = client.get_cliff_ramp(&vesting_id);
+
+        env.crypto()
+            .sha256(
+                &(
+                    vesting.recipient,
+                    vesting.start_timestamp,
+                    vesting.end_timestamp,
+                    vesting.timelock,
+                    vesting.release_interval_secs,
+                    vesting.cliff_release_timestamp,
+                    vesting.initial_unlock,
+                    vesting.cliff_amount,
+                    vesting.linear_vest_amount,
+                    cliff_ramp_secs,
+                )
+                    .to_xdr(&env),
+            )
+            .into()
+    }
+
+    /// Returns the schedule type code of a vesting (0 for linear).
+    pub fn get_schedule_type(env: Env, manager: Address, vesting_id: u64) -> u32 {
+        // Panics if there is no vesting associated with the given id.
+        TokenVestingManagerClient::new(&env, &manager).get_vesting_info(&vesting_id);
+
+        SCHEDULE_TYPE_LINEAR
+    }
+
+    /// Retrieves information about a specific vesting arrangement as a flat tuple, in the order:
+    /// recipient, start timestamp, end timestamp, deactivation timestamp, timelock,
+    /// release interval, cliff release timestamp, initial unlock, cliff amount,
+    /// linear vest amount and claimed amount.
+    pub fn get_vesting_flat(
+        env: Env,
+        manager: Address,
+        vesting_id: u64,
+    ) -> (
+        Address,
+        u64,
+        u64,
+        u64,
+        u64,
+        u64,
+        u64,
+        i128,
+        i128,
+        i128,
+        i128,
+    ) {
+        let vesting = TokenVestingManagerClient::new(&env, &manager).get_vesting_info(&vesting_id);
+
+        (
+            vesting.recipient,
+            vesting.start_timestamp,
+            vesting.end_timestamp,
+            vesting.deactivation_timestamp,
+            vesting.timelock,
+            vesting.release_interval_secs,
+            vesting.cliff_release_timestamp,
+            vesting.initial_unlock,
+            vesting.cliff_amount,
+            vesting.linear_vest_amount,
+            vesting.claimed_amount,
+        )
+    }
+
+    /// Returns whether a feature is available in the current configuration of the manager.
+    /// `PAUSE` and `SUNSET` are always available; use `is_claims_paused` and `is_sunset` of the
+    /// manager for their state. The configurable features are reported as enabled while their
+    /// setting is on: `ACK`, `COOLDOWN`, `RECLAIM`, `WDELAY`, `ADMDELAY`, `MULTISIG`, `REVAPPR`,
+    /// `RECOVERY`, `MAXPERREC`, `MINDUR`, `MAXSTART`, `DEFINTVL`, `SINGLEINT`, `ALLOWLIST` and
+    /// `BLKADMREC`. Unknown features, such as `FEE`, are reported as unavailable.
+    pub fn has_feature(env: Env, manager: Address, feature: Symbol) -> bool {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        if feature == symbol_short!("PAUSE") || feature == symbol_short!("SUNSET") {
+            true
+        } else if feature == symbol_short!("ACK") {
+            client.is_ack_required()
+        } else if feature == symbol_short!("COOLDOWN") {
+            client.get_claim_cooldown() != 0
+        } else if feature == symbol_short!("RECLAIM") {
+            client.get_reclaim_grace_secs() != 0
+        } else if feature == symbol_short!("WDELAY") {
+            client.get_withdraw_delay() != 0
+        } else if feature == symbol_short!("ADMDELAY") {
+            client.get_admin_change_delay() != 0
+        } else if feature == symbol_short!("MULTISIG") {
+            client.get_action_threshold() > 1
+        } else if feature == symbol_short!("REVAPPR") {
+            client.get_revoke_approval_threshold() != 0
+        } else if feature == symbol_short!("RECOVERY") {
+            client.get_recovery_address().is_some()
+        } else if feature == symbol_short!("MAXPERREC") {
+            client.get_max_vestings_per_recipient() != 0
+        } else if feature == symbol_short!("MINDUR") {
+            client.get_min_duration_secs() != 0
+        } else if feature == symbol_short!("MAXSTART") {
+            client.get_max_future_start_secs() != 0
+        } else if feature == symbol_short!("DEFINTVL") {
+            client.get_default_release_interval() != 0
+        } else if feature == symbol_short!("SINGLEINT") {
+            client.is_single_interval_allowed()
+        } else if feature == symbol_short!("ALLOWLIST") {
+            client.is_recipient_allowlist_enabled()
+        } else if feature == symbol_short!("BLKADMREC") {
+            client.is_admin_recipients_blocked()
+        } else {
+            false
+        }
+    }
+
+    /// Returns the maximum number of entries a single call of an aggregate view can be asked to
+    /// scan. Larger ranges panic, so callers page through with consecutive ranges.
+    pub fn get_max_scan(_env: Env) -> u32 {
+        MAX_SCAN
+    }
+
+    /// Returns the vestings with ids from `from_id` (inclusive) to `to_id` (exclusive),
+    /// skipping missing ids. The range can span at most `MAX_SCAN` ids.
+    pub fn get_all_vestings_sliced(
+        env: Env,
+        manager: Address,
+        from_id: u64,
+        to_id: u64,
+    ) -> Vec<Vesting> {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut vestings: Vec<Vesting> = Vec::new(&env);
+        for vesting_id in Self::id_range(from_id, to_id) {
+            if let Some(vesting) = client.get_vesting_info_or_default(&vesting_id) {
+                vestings.push_back(vesting);
+            }
+        }
+
+        vestings
+    }
+
+    /// Returns the ids from `from_id` (inclusive) to `to_id` (exclusive) of the vestings with
+    /// tokens left to claim whose next claimable timestamp is at or before `deadline_timestamp`.
+    /// The range can span at most `MAX_SCAN` ids.
+    pub fn ids_unlocking_before(
+        env: Env,
+        manager: Address,
+        deadline_timestamp: u64,
+        from_id: u64,
+        to_id: u64,
+    ) -> Vec<u64> {
+        let mut vesting_ids: Vec<u64> = Vec::new(&env);
+        if deadline_timestamp < env.ledger().timestamp() {
+            return vesting_ids;
+        }
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        // The vested amount never decreases, so a vesting has a claimable timestamp at or
+        // before the deadline exactly when it has a positive claimable amount at the deadline.
+        for vesting_id in Self::id_range(from_id, to_id) {
+            if client.get_vesting_info_or_default(&vesting_id).is_some()
+                && client.get_claimable_amount(&vesting_id, &deadline_timestamp) > 0
+            {
+                vesting_ids.push_back(vesting_id);
+            }
+        }
+
+        vesting_ids
+    }
+
+    /// Returns the minimum, maximum and sum of the total amounts of the vestings from `from_id`
+    /// (inclusive) to `to_id` (exclusive), with the number of vestings found. Missing ids are
+    /// skipped, and all values are 0 when no vesting is found. The range can span at most
+    /// `MAX_SCAN` ids.
+    pub fn get_vesting_size_stats(
+        env: Env,
+        manager: Address,
+        from_id: u64,
+        to_id: u64,
+    ) -> (i128, i128, i128, u32) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut min_total: i128 = 0;
+        let mut max_total: i128 = 0;
+        let mut sum_total: i128 = 0;
+        let mut count: u32 = 0;
+        for vesting_id in Self::id_range(from_id, to_id) {
+            let vesting = match client.get_vesting_info_or_default(&vesting_id) {
+                Some(vesting) => vesting,
+                None => continue,
+            };
+
+            let total = vesting.initial_unlock + vesting.cliff_amount + vesting.linear_vest_amount;
+            if count == 0 || total < min_total {
+                min_total = total;
+            }
+            if count == 0 || total > max_total {
+                max_total = total;
+            }
+            sum_total = sum_total
+                .checked_add(total)
+                .unwrap_or_else(|| panic!("Amount overflow"));
+            count += 1;
+        }
+
+        (min_total, max_total, sum_total, count)
+    }
+
+    /// Returns the amount newly vesting within `[window_start, window_end)` across the vestings
+    /// from `from_id` (inclusive) to `to_id` (exclusive). Revoked vestings only count what vests
+    /// before their deactivation. The range can span at most `MAX_SCAN` ids.
+    pub fn get_allocations_in_window(
+        env: Env,
+        manager: Address,
+        window_start: u64,
+        window_end: u64,
+        from_id: u64,
+        to_id: u64,
+    ) -> i128 {
+        if window_start >= window_end {
+            return 0;
+        }
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut allocations: i128 = 0;
+        for vesting_id in Self::id_range(from_id, to_id) {
+            let vesting = match client.get_vesting_info_or_default(&vesting_id) {
+                Some(vesting) => vesting,
+                None => continue,
+            };
+
+            // Amounts vested at the last second of the window minus the ones vested before it.
+            let vested_at_end = Self::vested_amount(&client, vesting_id, &vesting, window_end - 1);
+            let vested_before_start = if window_start == 0 {
+                0
+            } else {
+                Self::vested_amount(&client, vesting_id, &vesting, window_start - 1)
+            };
+
+            allocations = allocations
+                .checked_add(vested_at_end - vested_before_start)
+                .unwrap_or_else(|| panic!("Amount overflow"));
+        }
+
+        allocations
+    }
+
+    /// Splits the tokens reserved for the vestings from `from_id` (inclusive) to `to_id`
+    /// (exclusive) into the amount vested but not yet claimed and the amount still unvested at
+    /// the given timestamp. Over all vesting ids, both parts sum to the reserved amount.
+    /// The range can span at most `MAX_SCAN` ids.
+    pub fn get_reserved_breakdown(
+        env: Env,
+        manager: Address,
+        reference_timestamp: u64,
+        from_id: u64,
+        to_id: u64,
+    ) -> (i128, i128) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut vested_unclaimed: i128 = 0;
+        let mut unvested: i128 = 0;
+
+        for vesting_id in Self::id_range(from_id, to_id) {
+            let vesting = match client.get_vesting_info_or_default(&vesting_id) {
+                Some(vesting) => vesting,
+                None => continue,
+            };
+
+            let final_vest_amount =
+                Self::vested_amount(&client, vesting_id, &vesting, vesting.end_timestamp);
+            // Claims made after the reference timestamp count as vested.
+            let vest_amount =
+                Self::vested_amount(&client, vesting_id, &vesting, reference_timestamp)
+                    .max(vesting.claimed_amount);
+
+            vested_unclaimed += vest_amount - vesting.claimed_amount;
+            unvested += final_vest_amount - vest_amount;
+        }
+
+        (vested_unclaimed, unvested)
+    }
+
+    /// Returns the recipients in a specific range of the recipients list, `from` being inclusive and
+    /// `to` being exclusive, which have a positive claimable amount at the given timestamp.
+    /// The range is clamped to the recipients and can span at most `MAX_SCAN` recipients, each
+    /// with up to 100 vestings, so recipients with many vestings call for narrower ranges.
+    pub fn recipients_with_claimable(
+        env: Env,
+        manager: Address,
+        reference_timestamp: u64,
+        from: u32,
+        to: u32,
+    ) -> Vec<Address> {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let to = to.min(client.get_all_recipients_len());
+        let from = from.min(to);
+        assert!(to - from <= MAX_SCAN, "Range too large");
+
+        let mut recipients_with_claimable: Vec<Address> = Vec::new(&env);
+
+        for recipient in client.get_all_recipients_sliced(&from, &to).iter() {
+            let recipient_vestings =
+                client.get_all_recipient_vesting_sliced(&0, &u32::MAX, &recipient);
+            if recipient_vestings.iter().any(|vesting_id| {
+                client.get_claimable_amount(&vesting_id, &reference_timestamp) > 0
+            }) {
+                recipients_with_claimable.push_back(recipient);
+            }
+        }
+
+        recipients_with_claimable
+    }
+
+    /// Returns the vesting of a recipient at the given index of its vesting list.
+    pub fn get_recipient_vesting_by_index(
+        env: Env,
+        manager: Address,
+        recipient: Address,
+        index: u32,
+    ) -> Vesting {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting_id = client
+            .get_all_recipient_vesting_sliced(&index, &index.saturating_add(1), &recipient)
+            .get(0)
+            .unwrap_or_else(|| panic!("Vesting index out of range"));
+
+        client.get_vesting_info(&vesting_id)
+    }
+
+    /// Returns the number of active, revoked and fully claimed vestings for the recipient among
+    /// the vestings from `from` (inclusive) to `to` (exclusive) of its vesting list. The range
+    /// is clamped to the recipient vestings and can span at most `MAX_SCAN` vestings.
+    pub fn recipient_vesting_status_counts(
+        env: Env,
+        manager: Address,
+        recipient: Address,
+        from: u32,
+        to: u32,
+    ) -> (u32, u32, u32) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut active: u32 = 0;
+        let mut revoked: u32 = 0;
+        let mut fully_claimed: u32 = 0;
+
+        for vesting_id in Self::recipient_vesting_ids(&client, &recipient, from, to).iter() {
+            match Self::vesting_status(&client, vesting_id) {
+                STATUS_REVOKED => revoked += 1,
+                STATUS_DRAINED => fully_claimed += 1,
+                _ => active += 1,
+            }
+        }
+
+        (active, revoked, fully_claimed)
+    }
+
+    /// Returns the vesting ids of the recipient with the given status (0 active, 1 revoked,
+    /// 2 fully claimed) among the vestings from `from` (inclusive) to `to` (exclusive) of its
+    /// vesting list. The range is clamped to the recipient vestings and can span at most
+    /// `MAX_SCAN` vestings.
+    pub fn recipient_vesting_ids_by_status(
+        env: Env,
+        manager: Address,
+        recipient: Address,
+        status: u32,
+        from: u32,
+        to: u32,
+    ) -> Vec<u64> {
+        assert!(status <= STATUS_DRAINED, "Invalid status");
+
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let mut matching_ids: Vec<u64> = Vec::new(&env);
+        for vesting_id in Self::recipient_vesting_ids(&client, &recipient, from, to).iter() {
+            if Self::vesting_status(&client, vesting_id) == status {
+                matching_ids.push_back(vesting_id);
+            }
+        }
+
+        matching_ids
+    }
+
+    /// Returns the vesting ids of the recipient from `from` (inclusive) to `to` (exclusive) of
+    /// its vesting list, their statuses at `reference_timestamp` and the total claimable across
+    /// them. The range is clamped to the recipient vestings and can span at most `MAX_SCAN`
+    /// vestings.
+    pub fn get_recipient_bundle(
+        env: Env,
+        manager: Address,
+        recipient: Address,
+        reference_timestamp: u64,
+        from: u32,
+        to: u32,
+    ) -> (Vec<u64>, Vec<VestingStatus>, i128) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        let vesting_ids = Self::recipient_vesting_ids(&client, &recipient, from, to);
+
+        let mut statuses: Vec<VestingStatus> = Vec::new(&env);
+        let mut total_claimable: i128 = 0;
+        for vesting_id in vesting_ids.iter() {
+            let vesting = client.get_vesting_info(&vesting_id);
+            let claimable_amount = client.get_claimable_amount(&vesting_id, &reference_timestamp);
+            total_claimable += claimable_amount;
+
+            statuses.push_back(VestingStatus {
+                status: Self::vesting_status(&client, vesting_id),
+                vested_amount: Self::vested_amount(
+                    &client,
+                    vesting_id,
+                    &vesting,
+                    reference_timestamp,
+                ),
+                claimed_amount: vesting.claimed_amount,
+                claimable_amount,
+            });
+        }
+
+        (vesting_ids, statuses, total_claimable)
+    }
+
+    /// Returns the token address, the amount reserved for vesting and the manager balance in a
+    /// single call.
+    pub fn get_token_and_reserved(env: Env, manager: Address) -> (Address, i128, i128) {
+        let client = TokenVestingManagerClient::new(&env, &manager);
+
+        (
+            client.get_token_address(),
+            client.get_tokens_reserved_for_vesting(),
+            client.get_balance(),
+        )
+    }
+
+    /// Returns the symbol of the token used in the manager.
+    pub fn get_token_symbol(env: Env, manager: Address) -> String {
+        let token_address = TokenVestingManagerClient::new(&env, &manager).get_token_address();
+
+        match TokenClient::new(&env, &token_address).try_symbol() {
+            Ok(Ok(symbol)) => symbol,
+            _ => panic!("Token metadata unavailable"),
+        }
+    }
+
+    /// Returns the name of the token used in the manager.
+    pub fn get_token_name(env: Env, manager: Address) -> String {
+        let token_address = TokenVestingManagerClient::new(&env, &manager).get_token_address();
+
+        match TokenClient::new(&env, &token_address).try_name() {
+            Ok(Ok(name)) => name,
+            _ => panic!("Token metadata unavailable"),
+        }
+    }
+
+    /// Returns true if the manager holds enough tokens to cover all the tokens reserved for vesting.
+    pub fn is_solvent(env: Env, manager: Address) -> bool {
+        TokenVestingManagerClient::new(&env, &manager).amount_to_withdraw_by_admin() >= 0
+    }
+
+    /// Returns the action hash to approve with `propose_action` for a `withdraw_admin` call.
+    pub fn get_withdraw_action_hash(
+        env: Env,
+        caller: Address,
+        amount_requested: i128,
+    ) -> BytesN<32> {
+        withdraw_action_hash(&env, caller, amount_requested)
+    }
+
+    /// Returns the action hash to approve with `propose_action` for a `set_admin` or
+    /// `propose_admin_change` call.
+    pub fn get_set_admin_action_hash(
+        env: Env,
+        caller: Address,
+        admin: Address,
+        is_enabled: bool,
+    ) -> BytesN<32> {
+        set_admin_action_hash(&env, caller, admin, is_enabled)
+    }
+
+    /// Returns the action hash to approve with `propose_action` for a `replace_admins` call.
+    pub fn get_replace_admins_action_hash(
+        env: Env,
+        caller: Address,
+        new_admins: Vec<Address>,
+    ) -> BytesN<32> {
+        replace_admins_action_hash(&env, caller, new_admins)
+    }
+
+    /// Returns the action hash to approve with `propose_action` for a `set_action_threshold`
+    /// call.
+    pub fn get_threshold_action_hash(
+        env: Env,
+        caller: Address,
+        threshold: u32,
+        window_secs: u64,
+    ) -> BytesN<32> {
+        threshold_action_hash(&env, caller, threshold, window_secs)
+    }
+
+    /// Returns the action hash to approve with `propose_action` for an `upgrade` or
+    /// `propose_upgrade` call.
+    pub fn get_upgrade_action_hash(
+        env: Env,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> BytesN<32> {
+        upgrade_action_hash(&env, caller, new_wasm_hash)
+    }
+
+    /// Returns the action hash to approve with `propose_action` for a `set_recovery` call.
+    pub fn get_recovery_action_hash(
+        env: Env,
+        caller: Address,
+        recovery: Option<Address>,
+        delay_secs: u64,
+    ) -> BytesN<32> {
+        recovery_action_hash(&env, caller, recovery, delay_secs)
+    }
+
+    /// Returns the vested amount of a stored vesting at the given timestamp, including its cliff
+    /// ramp.
+    fn vested_amount(
+        client: &TokenVestingManagerClient,
+        vesting_id: u64,
+        vesting: &Vesting,
+        reference_timestamp: u64,
+    ) -> i128 {
+        client.calculate_ramped_vested_amount(
+            vesting,
+            &reference_timestamp,
+            &client.get_cliff_ramp(&vesting_id),
+        )
+    }
+
+    /// Returns the first timestamp at or after `reference_timestamp` at which the vested amount of
+    /// a vesting exceeds `amount`, or its end timestamp if it never does.
+    fn first_timestamp_vesting_beyond(
+        client: &TokenVestingManagerClient,
+        vesting: &Vesting,
+        cliff_ramp_secs: u64,
+        amount: i128,
+        reference_timestamp: u64,
+    ) -> u64 {
+        let vests_beyond_amount = |timestamp: u64| {
+            client.calculate_ramped_vested_amount(vesting, &timestamp, &cliff_ramp_secs) > amount
+        };
+
+        if !vests_beyond_amount(vesting.end_timestamp) {
+            return vesting.end_timestamp;
+        }
+        if vests_beyond_amount(reference_timestamp) {
+            return reference_timestamp;
+        }
+
+        // The vested amount never decreases, so the first timestamp vesting beyond the amount can
+        // be found by bisection.
+        let mut low = reference_timestamp;
+        let mut high = vesting.end_timestamp;
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if vests_beyond_amount(middle) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        high
+    }
+
+    /// Returns the timestamp from which the linear amount of a vesting starts vesting.
+    fn linear_start(vesting: &Vesting) -> u64 {
+        if vesting.cliff_release_timestamp != 0 {
+            vesting.cliff_release_timestamp
+        } else {
+            vesting.start_timestamp
+        }
+    }
+
+    /// Returns the ids from `from_id` (inclusive) to `to_id` (exclusive), panicking if the range
+    /// spans more than `MAX_SCAN` ids.
+    fn id_range(from_id: u64, to_id: u64) -> core::ops::Range<u64> {
+        assert!(
+            to_id.saturating_sub(from_id) <= MAX_SCAN as u64,
+            "Range too large"
+        );
+
+        from_id..to_id
+    }
+
+    /// Returns the vesting ids of the recipient from `from` (inclusive) to `to` (exclusive) of
+    /// its vesting list, panicking if the range spans more than `MAX_SCAN` vestings.
+    fn recipient_vesting_ids(
+        client: &TokenVestingManagerClient,
+        recipient: &Address,
+        from: u32,
+        to: u32,
+    ) -> Vec<u64> {
+        assert!(to.saturating_sub(from) <= MAX_SCAN, "Range too large");
+
+        client.get_all_recipient_vesting_sliced(&from, &to, recipient)
+    }
+
+    /// Returns the status code of a vesting: revoked, fully claimed or active.
+    fn vesting_status(client: &TokenVestingManagerClient, vesting_id: u64) -> u32 {
+        let vesting = client.get_vesting_info(&vesting_id);
+
+        let final_vest_amount =
+            Self::vested_amount(client, vesting_id, &vesting, vesting.end_timestamp);
+
+        if vesting.deactivation_timestamp != 0 {
+            STATUS_REVOKED
+        } else if final_vest_amount == vesting.claimed_amount {
+            STATUS_DRAINED
+        } else {
+            STATUS_ACTIVE
+        }
+    }
+}
+
+mod test;
@@ -0,0 +1,2267 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{testutils::Address as TestAddress, testutils::Ledger, vec, Env};
+use token_vesting_manager::{TokenVestingManager, TokenVestingManagerClient as ManagerClient};
+
+/// Manager holding a single arbitrary vesting without a cliff ramp, standing for schedules the
+/// manager does not create itself.
+#[contract]
+pub struct MockManager;
+
+#[contractimpl]
+impl MockManager {
+    pub fn set_vesting(env: Env, vesting: Vesting) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("VESTING"), &vesting);
+    }
+
+    pub fn get_vesting_info(env: Env, _vesting_id: u64) -> Vesting {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("VESTING"))
+            .unwrap()
+    }
+
+    pub fn get_cliff_ramp(_env: Env, _vesting_id: u64) -> u64 {
+        0
+    }
+
+    pub fn calculate_ramped_vested_amount(
+        env: Env,
+        vesting: Vesting,
+        reference_timestamp: u64,
+        cliff_ramp_secs: u64,
+    ) -> i128 {
+        TokenVestingManager::calculate_ramped_vested_amount(
+            env,
+            vesting,
+            reference_timestamp,
+            cliff_ramp_secs,
+        )
+    }
+}
+
+fn deploy_manager_helper(
+    env: &Env,
+) -> (
+    ManagerClient<'_>,
+    Address,
+    TokenClient<'_>,
+    StellarAssetClient<'_>,
+    Address,
+) {
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = ManagerClient::new(env, &contract_id);
+
+    let admin: Address = Address::generate(env);
+    let (token_client, token_admin_client, token_address) = deploy_token_helper(env);
+    client.init(&admin, &token_address, &None, &0);
+
+    (
+        client,
+        admin,
+        token_client,
+        token_admin_client,
+        token_address,
+    )
+}
+
+fn deploy_token_helper(env: &Env) -> (TokenClient<'_>, StellarAssetClient<'_>, Address) {
+    let token_admin = Address::generate(env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone());
+    let token_admin_client: StellarAssetClient<'_> =
+        StellarAssetClient::new(env, &token_contract_id.address());
+    let token_client = TokenClient::new(env, &token_contract_id.address());
+
+    (
+        token_client,
+        token_admin_client,
+        token_contract_id.address(),
+    )
+}
+
+fn deploy_reader_helper(env: &Env) -> TokenVestingReaderClient<'_> {
+    let contract_id = env.register(TokenVestingReader, ());
+
+    TokenVestingReaderClient::new(env, &contract_id)
+}
+
+#[test]
+fn test_get_schedule_grid() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 100;
+    let cliff_release_timestamp: u64 = 1200;
+    let initial_unlock: i128 = 500;
+    let cliff_amount: i128 = 300;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Start, cliff and 8 linear interval boundaries.
+    let grid = reader.get_schedule_grid(&client.address, &vesting_id, &20);
+    assert_eq!(grid.len(), 10);
+    assert_eq!(grid.get(0).unwrap(), (start_timestamp, initial_unlock));
+    assert_eq!(
+        grid.get(1).unwrap(),
+        (cliff_release_timestamp, initial_unlock + cliff_amount)
+    );
+    assert_eq!(
+        grid.get(2).unwrap(),
+        (
+            cliff_release_timestamp + 100,
+            initial_unlock + cliff_amount + 125
+        )
+    );
+    assert_eq!(grid.get(9).unwrap(), (end_timestamp, total_expected_amount));
+
+    // Sampled grid keeps the first and last points.
+    let sampled_grid = reader.get_schedule_grid(&client.address, &vesting_id, &4);
+    assert_eq!(sampled_grid.len(), 4);
+    assert_eq!(
+        sampled_grid.get(0).unwrap(),
+        (start_timestamp, initial_unlock)
+    );
+    assert_eq!(
+        sampled_grid.get(3).unwrap(),
+        (end_timestamp, total_expected_amount)
+    );
+}
+
+#[test]
+fn test_get_token_symbol_and_name() {
+    let env = Env::default();
+    let (client, _, token_client, _, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    assert_eq!(
+        reader.get_token_symbol(&client.address),
+        token_client.symbol()
+    );
+    assert_eq!(reader.get_token_name(&client.address), token_client.name());
+}
+
+#[test]
+#[should_panic]
+fn test_get_token_symbol_without_metadata() {
+    let env = Env::default();
+    let contract_id = env.register(TokenVestingManager, ());
+    let client = ManagerClient::new(&env, &contract_id);
+    let reader = deploy_reader_helper(&env);
+
+    let admin: Address = Address::generate(&env);
+    let token_address: Address = Address::generate(&env);
+    client.init(&admin, &token_address, &None, &0);
+
+    // Panics because the token does not implement the token metadata.
+    reader.get_token_symbol(&client.address);
+}
+
+#[test]
+fn test_recipients_with_claimable() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let claimable_recipient: Address = Address::generate(&env);
+    let timelocked_recipient: Address = Address::generate(&env);
+    let fully_claimed_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = 3 * linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &claimable_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    client.create_vesting(
+        &admin,
+        &timelocked_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &(end_timestamp + 1000),
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let fully_claimed_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &fully_claimed_recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&fully_claimed_recipient, &fully_claimed_vesting_id);
+
+    assert_eq!(
+        reader.recipients_with_claimable(&client.address, &end_timestamp, &0, &3),
+        vec![&env, claimable_recipient.clone()]
+    );
+    assert_eq!(
+        reader.recipients_with_claimable(&client.address, &end_timestamp, &1, &3),
+        vec![&env]
+    );
+    // The range is clamped to the number of recipients.
+    assert_eq!(
+        reader.recipients_with_claimable(&client.address, &end_timestamp, &0, &100),
+        vec![&env, claimable_recipient]
+    );
+    // Once the timelock lifts, the timelocked recipient has a claimable amount too.
+    assert_eq!(
+        reader.recipients_with_claimable(&client.address, &(end_timestamp + 1000), &1, &3),
+        vec![&env, timelocked_recipient]
+    );
+}
+
+#[test]
+fn test_is_solvent() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    assert!(reader.is_solvent(&client.address));
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert!(reader.is_solvent(&client.address));
+
+    // Tokens backing the vesting are burned from the contract, leaving it under-funded.
+    token_client.burn(&client.address, &1);
+
+    assert!(!reader.is_solvent(&client.address));
+}
+
+#[test]
+fn test_claimable_ignoring_timelock() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = end_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let reference_timestamp: u64 = start_timestamp + 500;
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &reference_timestamp),
+        0
+    );
+    assert_eq!(
+        reader.claimable_ignoring_timelock(&client.address, &vesting_id, &reference_timestamp),
+        1500
+    );
+
+    // Once the timelock lifts, both amounts match.
+    assert_eq!(client.get_claimable_amount(&vesting_id, &timelock), 2000);
+    assert_eq!(
+        reader.claimable_ignoring_timelock(&client.address, &vesting_id, &timelock),
+        2000
+    );
+}
+
+#[test]
+fn test_recipient_vesting_status_counts() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..3 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    assert_eq!(
+        reader.recipient_vesting_status_counts(&client.address, &recipient, &0, &10),
+        (3, 0, 0)
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_vesting(&admin, &vesting_ids.get(1).unwrap());
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_ids.get(2).unwrap());
+
+    assert_eq!(
+        reader.recipient_vesting_status_counts(&client.address, &recipient, &0, &10),
+        (1, 1, 1)
+    );
+    assert_eq!(
+        reader.recipient_vesting_status_counts(&client.address, &recipient, &1, &3),
+        (0, 1, 1)
+    );
+    assert_eq!(
+        reader.recipient_vesting_status_counts(&client.address, &Address::generate(&env), &0, &10),
+        (0, 0, 0)
+    );
+    assert!(reader
+        .try_recipient_vesting_status_counts(
+            &client.address,
+            &recipient,
+            &0,
+            &(reader.get_max_scan() + 1)
+        )
+        .is_err());
+}
+
+#[test]
+fn test_get_all_vestings_sliced() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 4));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 4),
+        &expiration_ledger,
+    );
+
+    let mut recipients: Vec<Address> = Vec::new(&env);
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..4 {
+        let recipient: Address = Address::generate(&env);
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+        recipients.push_back(recipient);
+    }
+
+    let first_page = reader.get_all_vestings_sliced(&client.address, &0, &2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(
+        first_page.get(0).unwrap().recipient,
+        recipients.get(0).unwrap()
+    );
+    assert_eq!(
+        first_page.get(1).unwrap().recipient,
+        recipients.get(1).unwrap()
+    );
+
+    // The last page is clamped to the existing ids.
+    let last_page = reader.get_all_vestings_sliced(&client.address, &2, &10);
+    assert_eq!(last_page.len(), 2);
+    assert_eq!(
+        last_page.get(0).unwrap().recipient,
+        recipients.get(2).unwrap()
+    );
+    assert_eq!(
+        last_page.get(1).unwrap().recipient,
+        recipients.get(3).unwrap()
+    );
+
+    // Purged ids are skipped.
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipients.get(1).unwrap(), &vesting_ids.get(1).unwrap());
+    client.purge_vesting(&admin, &vesting_ids.get(1).unwrap());
+
+    let all_vestings = reader.get_all_vestings_sliced(&client.address, &0, &4);
+    assert_eq!(all_vestings.len(), 3);
+    assert_eq!(
+        all_vestings.get(1).unwrap().recipient,
+        recipients.get(2).unwrap()
+    );
+}
+
+#[test]
+fn test_get_timelock_expiry() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &10000);
+    token_client.approve(&admin, &client.address, &10000, &expiration_ledger);
+
+    // No timelock, no initial unlock and no cliff: the first linear interval.
+    let linear_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &0,
+        &0,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &linear_vesting_id),
+        start_timestamp + release_interval_secs
+    );
+
+    // No timelock with an initial unlock: the start.
+    let initial_unlock_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &100,
+        &0,
+        &0,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &initial_unlock_vesting_id),
+        start_timestamp
+    );
+
+    // No timelock with a cliff: the cliff release.
+    let cliff_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &(start_timestamp + 500),
+        &100,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &cliff_vesting_id),
+        start_timestamp + 500
+    );
+
+    // Timelock before the cliff: the cliff release.
+    let early_timelock_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &(start_timestamp + 200),
+        &0,
+        &(start_timestamp + 500),
+        &100,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &early_timelock_vesting_id),
+        start_timestamp + 500
+    );
+
+    // Timelock after the cliff: the timelock.
+    let late_timelock_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &(start_timestamp + 700),
+        &0,
+        &(start_timestamp + 500),
+        &100,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &late_timelock_vesting_id),
+        start_timestamp + 700
+    );
+
+    // Ramped cliff: the first second of the ramp, before the first linear interval.
+    let ramped_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &0,
+        &(start_timestamp + 500),
+        &100,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    client.set_cliff_ramp(&admin, &ramped_vesting_id, &100);
+    assert_eq!(
+        reader.get_timelock_expiry(&client.address, &ramped_vesting_id),
+        start_timestamp + 501
+    );
+}
+
+#[test]
+fn test_get_timelock_expiry_without_cliff_or_linear_amount() {
+    let env = Env::default();
+    let reader = deploy_reader_helper(&env);
+    let manager = MockManagerClient::new(&env, &env.register(MockManager, ()));
+
+    let start_timestamp: u64 = 1000;
+    let vesting = Vesting {
+        recipient: Address::generate(&env),
+        start_timestamp,
+        end_timestamp: start_timestamp + 1000,
+        deactivation_timestamp: 0,
+        timelock: 0,
+        release_interval_secs: 10,
+        cliff_release_timestamp: start_timestamp + 500,
+        initial_unlock: 0,
+        cliff_amount: 0,
+        linear_vest_amount: 1000,
+        claimed_amount: 0,
+    };
+
+    // Cliff without a cliff amount: the first linear interval after the cliff.
+    manager.set_vesting(&vesting);
+    assert_eq!(
+        reader.get_timelock_expiry(&manager.address, &0),
+        start_timestamp + 510
+    );
+
+    // Initial unlock only: the start.
+    manager.set_vesting(&Vesting {
+        cliff_release_timestamp: 0,
+        initial_unlock: 100,
+        linear_vest_amount: 0,
+        ..vesting.clone()
+    });
+    assert_eq!(
+        reader.get_timelock_expiry(&manager.address, &0),
+        start_timestamp
+    );
+
+    // Nothing to vest: the end.
+    manager.set_vesting(&Vesting {
+        cliff_release_timestamp: 0,
+        linear_vest_amount: 0,
+        ..vesting
+    });
+    assert_eq!(
+        reader.get_timelock_expiry(&manager.address, &0),
+        start_timestamp + 1000
+    );
+}
+
+#[test]
+fn test_aggregate_views_bounded_scan() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let release_interval_secs: u64 = 10;
+    let linear_vest_amount: i128 = 100;
+    let expiration_ledger: u32 = 6300000;
+
+    let max_scan: u32 = reader.get_max_scan();
+    let number_of_vestings: u32 = max_scan + 5;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * number_of_vestings as i128));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * number_of_vestings as i128),
+        &expiration_ledger,
+    );
+
+    for _ in 0..number_of_vestings {
+        client.create_vesting(
+            &admin,
+            &Address::generate(&env),
+            &start_timestamp,
+            &end_timestamp,
+            &0,
+            &0,
+            &0,
+            &0,
+            &release_interval_secs,
+            &linear_vest_amount,
+        );
+    }
+
+    // A single call scans at most `max_scan` entries, larger ranges panic instead of being
+    // silently truncated.
+    let first_page = reader.get_all_vestings_sliced(&client.address, &0, &(max_scan as u64));
+    assert_eq!(first_page.len(), max_scan);
+    let second_page =
+        reader.get_all_vestings_sliced(&client.address, &(max_scan as u64), &(max_scan as u64 * 2));
+    assert_eq!(second_page.len(), 5);
+    assert!(reader
+        .try_get_all_vestings_sliced(&client.address, &0, &(max_scan as u64 + 1))
+        .is_err());
+
+    let first_page =
+        reader.recipients_with_claimable(&client.address, &end_timestamp, &0, &max_scan);
+    assert_eq!(first_page.len(), max_scan);
+    // The range is clamped to the recipients before being checked.
+    let second_page =
+        reader.recipients_with_claimable(&client.address, &end_timestamp, &max_scan, &u32::MAX);
+    assert_eq!(second_page.len(), 5);
+    assert!(reader
+        .try_recipients_with_claimable(&client.address, &end_timestamp, &0, &u32::MAX)
+        .is_err());
+
+    let unlocking_ids =
+        reader.ids_unlocking_before(&client.address, &end_timestamp, &0, &(max_scan as u64));
+    assert_eq!(unlocking_ids.len(), max_scan);
+    let (vested_unclaimed, unvested) =
+        reader.get_reserved_breakdown(&client.address, &start_timestamp, &0, &(max_scan as u64));
+    assert_eq!(
+        vested_unclaimed + unvested,
+        linear_vest_amount * max_scan as i128
+    );
+    for (from_id, to_id) in [(0, u64::MAX), (5, max_scan as u64 + 6)] {
+        assert!(reader
+            .try_get_reserved_breakdown(&client.address, &start_timestamp, &from_id, &to_id)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_get_vesting_flat() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = start_timestamp + 100;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 600);
+    client.claim(&recipient, &vesting_id);
+    client.revoke_vesting(&admin, &vesting_id);
+
+    let vesting = client.get_vesting_info(&vesting_id);
+
+    assert_eq!(
+        reader.get_vesting_flat(&client.address, &vesting_id),
+        (
+            vesting.recipient,
+            vesting.start_timestamp,
+            vesting.end_timestamp,
+            vesting.deactivation_timestamp,
+            vesting.timelock,
+            vesting.release_interval_secs,
+            vesting.cliff_release_timestamp,
+            vesting.initial_unlock,
+            vesting.cliff_amount,
+            vesting.linear_vest_amount,
+            vesting.claimed_amount,
+        )
+    );
+}
+
+#[test]
+fn test_get_schedule_type() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(reader.get_schedule_type(&client.address, &vesting_id), 0);
+    assert!(reader
+        .try_get_schedule_type(&client.address, &(vesting_id + 1))
+        .is_err());
+}
+
+#[test]
+fn test_has_feature() {
+    let env = Env::default();
+    let (client, admin, _, _, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    // Mock the admin.
+    env.mock_all_auths();
+
+    let configurable_features = [
+        symbol_short!("ACK"),
+        symbol_short!("COOLDOWN"),
+        symbol_short!("RECLAIM"),
+        symbol_short!("WDELAY"),
+        symbol_short!("ADMDELAY"),
+        symbol_short!("REVAPPR"),
+        symbol_short!("RECOVERY"),
+        symbol_short!("MINDUR"),
+        symbol_short!("MAXSTART"),
+        symbol_short!("DEFINTVL"),
+        symbol_short!("BLKADMREC"),
+    ];
+    for feature in configurable_features.iter() {
+        assert!(!reader.has_feature(&client.address, feature));
+    }
+
+    // Pausing and sunsetting are always available, whatever their state.
+    assert!(reader.has_feature(&client.address, &symbol_short!("PAUSE")));
+    assert!(reader.has_feature(&client.address, &symbol_short!("SUNSET")));
+
+    client.set_require_ack(&admin, &true);
+    client.set_claim_cooldown(&admin, &100);
+    client.set_reclaim_grace_secs(&admin, &86400);
+    client.set_withdraw_delay(&admin, &3600);
+    client.set_admin_change_delay(&admin, &3600);
+    client.set_revoke_approval_threshold(&admin, &500);
+    client.set_recovery(&admin, &Some(Address::generate(&env)), &2_592_000);
+    client.set_min_duration_secs(&admin, &100);
+    client.set_max_future_start_secs(&admin, &86400);
+    client.set_default_release_interval(&admin, &10);
+    client.set_block_admin_recipients(&admin, &true);
+
+    for feature in configurable_features.iter() {
+        assert!(reader.has_feature(&client.address, feature));
+    }
+
+    client.set_claims_paused(&admin, &true, &None);
+    assert!(reader.has_feature(&client.address, &symbol_short!("PAUSE")));
+
+    client.set_claim_cooldown(&admin, &0);
+    assert!(!reader.has_feature(&client.address, &symbol_short!("COOLDOWN")));
+
+    // Features not supported by the contract are reported as unavailable.
+    assert!(!reader.has_feature(&client.address, &symbol_short!("FEE")));
+}
+
+#[test]
+fn test_get_effective_linear_start() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let initial_unlock: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &3000);
+    token_client.approve(&admin, &client.address, &3000, &expiration_ledger);
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &0,
+        &0,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_effective_linear_start(&client.address, &vesting_id),
+        start_timestamp
+    );
+
+    let cliff_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &(start_timestamp + 300),
+        &500,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_effective_linear_start(&client.address, &cliff_vesting_id),
+        start_timestamp + 300
+    );
+}
+
+#[test]
+fn test_simulate_revoke() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 800;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &total_expected_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &total_expected_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Before the start nothing would be kept.
+    assert_eq!(
+        reader.simulate_revoke(&client.address, &vesting_id, &(start_timestamp - 1)),
+        (total_expected_amount, 0)
+    );
+    // After the end nothing would be refunded.
+    assert_eq!(
+        reader.simulate_revoke(&client.address, &vesting_id, &end_timestamp),
+        (0, total_expected_amount)
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 300);
+    client.claim(&recipient, &vesting_id);
+    let claimed_amount: i128 = token_client.balance(&recipient);
+
+    let revoke_timestamp: u64 = start_timestamp + 605;
+    env.ledger().set_timestamp(revoke_timestamp);
+    let (refund_to_admin, recipient_keeps) =
+        reader.simulate_revoke(&client.address, &vesting_id, &revoke_timestamp);
+    assert_eq!(
+        refund_to_admin + recipient_keeps + claimed_amount,
+        total_expected_amount
+    );
+
+    // The simulation does not change state.
+    assert_eq!(
+        client.get_vesting_info(&vesting_id).deactivation_timestamp,
+        0
+    );
+    assert_eq!(
+        client.get_tokens_reserved_for_vesting(),
+        total_expected_amount - claimed_amount
+    );
+
+    client.revoke_vesting(&admin, &vesting_id);
+
+    assert_eq!(client.amount_to_withdraw_by_admin(), refund_to_admin);
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &end_timestamp),
+        recipient_keeps
+    );
+    assert_eq!(client.get_tokens_reserved_for_vesting(), recipient_keeps);
+
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        token_client.balance(&recipient),
+        claimed_amount + recipient_keeps
+    );
+
+    // A revoked vesting cannot be simulated.
+    assert!(reader
+        .try_simulate_revoke(&client.address, &vesting_id, &revoke_timestamp)
+        .is_err());
+}
+
+#[test]
+fn test_recipient_vesting_ids_by_status() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 5));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 5),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..5 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_vesting(&admin, &vesting_ids.get(1).unwrap());
+    client.revoke_vesting(&admin, &vesting_ids.get(3).unwrap());
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_ids.get(4).unwrap());
+
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(&client.address, &recipient, &0, &0, &10),
+        vec![
+            &env,
+            vesting_ids.get(0).unwrap(),
+            vesting_ids.get(2).unwrap()
+        ]
+    );
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(&client.address, &recipient, &1, &0, &10),
+        vec![
+            &env,
+            vesting_ids.get(1).unwrap(),
+            vesting_ids.get(3).unwrap()
+        ]
+    );
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(&client.address, &recipient, &2, &0, &10),
+        vec![&env, vesting_ids.get(4).unwrap()]
+    );
+
+    // Pages are taken over the vesting list of the recipient.
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(&client.address, &recipient, &1, &2, &5),
+        vec![&env, vesting_ids.get(3).unwrap()]
+    );
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(&client.address, &recipient, &2, &0, &4),
+        Vec::<u64>::new(&env)
+    );
+    assert_eq!(
+        reader.recipient_vesting_ids_by_status(
+            &client.address,
+            &Address::generate(&env),
+            &0,
+            &0,
+            &10
+        ),
+        Vec::<u64>::new(&env)
+    );
+
+    assert!(reader
+        .try_recipient_vesting_ids_by_status(&client.address, &recipient, &3, &0, &10)
+        .is_err());
+}
+
+#[test]
+fn test_next_claimable_timestamp() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 800;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let timelocked_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &(start_timestamp + 500),
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Before the cliff, the cliff release is the next unlock.
+    assert_eq!(
+        reader.next_claimable_timestamp(&client.address, &vesting_id, &start_timestamp),
+        cliff_release_timestamp
+    );
+    assert_eq!(
+        reader.next_claimable_timestamp(&client.address, &timelocked_vesting_id, &start_timestamp),
+        start_timestamp + 500
+    );
+
+    // Something is claimable right away.
+    assert_eq!(
+        reader.next_claimable_timestamp(
+            &client.address,
+            &vesting_id,
+            &(cliff_release_timestamp + 5)
+        ),
+        cliff_release_timestamp + 5
+    );
+
+    // Between interval boundaries, once claimed, the next boundary is the next unlock.
+    env.ledger().set_timestamp(cliff_release_timestamp + 15);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        reader.next_claimable_timestamp(
+            &client.address,
+            &vesting_id,
+            &(cliff_release_timestamp + 15)
+        ),
+        cliff_release_timestamp + 20
+    );
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(cliff_release_timestamp + 19)),
+        0
+    );
+    assert_eq!(
+        client.get_claimable_amount(&vesting_id, &(cliff_release_timestamp + 20)),
+        10
+    );
+
+    // A fully claimed vesting returns its end timestamp.
+    env.ledger().set_timestamp(end_timestamp + 100);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        reader.next_claimable_timestamp(&client.address, &vesting_id, &(end_timestamp + 100)),
+        end_timestamp
+    );
+}
+
+#[test]
+fn test_get_reserved_breakdown() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 800;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..3 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    assert_eq!(
+        reader.get_reserved_breakdown(&client.address, &start_timestamp, &0, &10),
+        (initial_unlock * 3, (cliff_amount + linear_vest_amount) * 3)
+    );
+
+    // One vesting is partly claimed, another one is revoked.
+    env.ledger().set_timestamp(start_timestamp + 400);
+    client.claim(&recipient, &vesting_ids.get(0).unwrap());
+    env.ledger().set_timestamp(start_timestamp + 600);
+    client.revoke_vesting(&admin, &vesting_ids.get(1).unwrap());
+
+    let reserved = client.get_tokens_reserved_for_vesting();
+    for reference_timestamp in [
+        start_timestamp,
+        start_timestamp + 400,
+        start_timestamp + 700,
+        end_timestamp,
+    ] {
+        let (vested_unclaimed, unvested) =
+            reader.get_reserved_breakdown(&client.address, &reference_timestamp, &0, &10);
+        assert_eq!(vested_unclaimed + unvested, reserved);
+    }
+
+    // 900 tokens are vested at +800, except for the vesting revoked at +600 with 700 tokens,
+    // and 500 tokens were claimed from the first vesting at +400.
+    assert_eq!(
+        reader.get_reserved_breakdown(&client.address, &(start_timestamp + 800), &0, &10),
+        ((900 - 500) + 700 + 900, (total_expected_amount - 900) * 2)
+    );
+    assert_eq!(
+        reader.get_reserved_breakdown(&client.address, &end_timestamp, &0, &10),
+        (reserved, 0)
+    );
+}
+
+#[test]
+fn test_ids_unlocking_before() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 100;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(linear_vest_amount * 4));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(linear_vest_amount * 4),
+        &expiration_ledger,
+    );
+
+    // The first unlock of each vesting happens one interval after its start.
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for start_timestamp in [1000_u64, 1500, 3000, 1000] {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &(start_timestamp + 1000),
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    // The last vesting is fully claimed and has nothing left to unlock.
+    env.ledger().set_timestamp(2000);
+    client.claim(&recipient, &vesting_ids.get(3).unwrap());
+    env.ledger().set_timestamp(500);
+
+    assert_eq!(
+        reader.ids_unlocking_before(&client.address, &1000, &0, &10),
+        Vec::<u64>::new(&env)
+    );
+    assert_eq!(
+        reader.ids_unlocking_before(&client.address, &1100, &0, &10),
+        vec![&env, vesting_ids.get(0).unwrap()]
+    );
+    assert_eq!(
+        reader.ids_unlocking_before(&client.address, &1600, &0, &10),
+        vec![
+            &env,
+            vesting_ids.get(0).unwrap(),
+            vesting_ids.get(1).unwrap()
+        ]
+    );
+    assert_eq!(
+        reader.ids_unlocking_before(&client.address, &5000, &0, &10),
+        vec![
+            &env,
+            vesting_ids.get(0).unwrap(),
+            vesting_ids.get(1).unwrap(),
+            vesting_ids.get(2).unwrap()
+        ]
+    );
+
+    // The range restricts the scanned ids.
+    assert_eq!(
+        reader.ids_unlocking_before(
+            &client.address,
+            &5000,
+            &vesting_ids.get(1).unwrap(),
+            &vesting_ids.get(2).unwrap()
+        ),
+        vec![&env, vesting_ids.get(1).unwrap()]
+    );
+}
+
+#[test]
+fn test_get_token_and_reserved() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, token_address) =
+        deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    assert_eq!(
+        reader.get_token_and_reserved(&client.address),
+        (token_address.clone(), 0, 0)
+    );
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &linear_vest_amount);
+    token_client.approve(
+        &admin,
+        &client.address,
+        &linear_vest_amount,
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &1000,
+        &2000,
+        &0,
+        &0,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+    token_admin_client.mint(&client.address, &50);
+
+    env.ledger().set_timestamp(1400);
+    client.claim(&recipient, &vesting_id);
+
+    assert_eq!(
+        reader.get_token_and_reserved(&client.address),
+        (token_address, 600, 650)
+    );
+}
+
+#[test]
+fn test_get_vesting_params_hash() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 1000;
+    let linear_vest_amount: i128 = 500;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let same_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let other_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &(end_timestamp + 1000),
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    let creation_hash = reader.get_vesting_params_hash(&client.address, &vesting_id);
+
+    // The hash only depends on the schedule parameters.
+    assert_eq!(
+        reader.get_vesting_params_hash(&client.address, &same_vesting_id),
+        creation_hash
+    );
+    assert_ne!(
+        reader.get_vesting_params_hash(&client.address, &other_vesting_id),
+        creation_hash
+    );
+
+    // Changing the cliff ramp alters the schedule, and so the hash.
+    client.set_cliff_ramp(&admin, &vesting_id, &100);
+    let ramped_hash = reader.get_vesting_params_hash(&client.address, &vesting_id);
+    assert_ne!(ramped_hash, creation_hash);
+
+    // Claims and revocations do not alter the schedule parameters.
+    env.ledger().set_timestamp(cliff_release_timestamp + 200);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        reader.get_vesting_params_hash(&client.address, &vesting_id),
+        ramped_hash
+    );
+
+    client.revoke_vesting(&admin, &same_vesting_id);
+    assert_eq!(
+        reader.get_vesting_params_hash(&client.address, &same_vesting_id),
+        creation_hash
+    );
+
+    assert!(reader
+        .try_get_vesting_params_hash(&client.address, &100)
+        .is_err());
+}
+
+#[test]
+fn test_get_vesting_size_stats() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 500;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &10_000);
+    token_client.approve(&admin, &client.address, &10_000, &expiration_ledger);
+
+    // Vesting totals: 1300, 600, 2300 and 800.
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for linear_vest_amount in [1000_i128, 300, 2000, 500] {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &0, &4),
+        (600, 2300, 5000, 4)
+    );
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &2, &4),
+        (800, 2300, 3100, 2)
+    );
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &0, &25),
+        (600, 2300, 5000, 4)
+    );
+
+    // Empty ranges report no vesting.
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &4, &10),
+        (0, 0, 0, 0)
+    );
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &3, &1),
+        (0, 0, 0, 0)
+    );
+
+    // Revoked vestings still count with their total amount.
+    env.ledger().set_timestamp(start_timestamp - 1);
+    client.revoke_vesting(&admin, &vesting_ids.get(1).unwrap());
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &0, &4),
+        (600, 2300, 5000, 4)
+    );
+
+    // Purged vestings are skipped.
+    client.purge_vesting(&admin, &vesting_ids.get(1).unwrap());
+    assert_eq!(
+        reader.get_vesting_size_stats(&client.address, &0, &4),
+        (800, 2300, 4400, 3)
+    );
+}
+
+#[test]
+fn test_get_claimed_percentage() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    let revoked_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    assert_eq!(
+        reader.get_claimed_percentage(&client.address, &vesting_id),
+        0
+    );
+
+    env.ledger().set_timestamp(start_timestamp + 250);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        reader.get_claimed_percentage(&client.address, &vesting_id),
+        2500
+    );
+
+    // A revoked vesting is measured against the amount vested at its deactivation.
+    client.claim(&recipient, &revoked_vesting_id);
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_vesting(&admin, &revoked_vesting_id);
+    assert_eq!(
+        reader.get_claimed_percentage(&client.address, &revoked_vesting_id),
+        5000
+    );
+    client.claim(&recipient, &revoked_vesting_id);
+    assert_eq!(
+        reader.get_claimed_percentage(&client.address, &revoked_vesting_id),
+        10000
+    );
+
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert_eq!(
+        reader.get_claimed_percentage(&client.address, &vesting_id),
+        10000
+    );
+
+    assert!(reader
+        .try_get_claimed_percentage(&client.address, &100)
+        .is_err());
+}
+
+#[test]
+fn test_get_interval_count() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let initial_unlock: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &3000);
+    token_client.approve(&admin, &client.address, &3000, &expiration_ledger);
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &0,
+        &0,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(reader.get_interval_count(&client.address, &vesting_id), 100);
+
+    // With a cliff, the linear amount vests from the cliff release timestamp.
+    let cliff_vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &(start_timestamp + 300),
+        &500,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_interval_count(&client.address, &cliff_vesting_id),
+        70
+    );
+
+    assert!(reader
+        .try_get_interval_count(&client.address, &100)
+        .is_err());
+}
+
+#[test]
+fn test_get_recipient_vesting_by_index() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let other_recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 0;
+    let cliff_amount: i128 = 0;
+
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &10_000);
+    token_client.approve(&admin, &client.address, &10_000, &expiration_ledger);
+
+    for (vesting_recipient, linear_vest_amount) in [
+        (&recipient, 1000_i128),
+        (&other_recipient, 2000),
+        (&recipient, 3000),
+    ] {
+        client.create_vesting(
+            &admin,
+            vesting_recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        );
+    }
+
+    assert_eq!(
+        reader
+            .get_recipient_vesting_by_index(&client.address, &recipient, &0)
+            .recipient,
+        client.get_vesting_info(&0).recipient
+    );
+    assert_eq!(
+        reader
+            .get_recipient_vesting_by_index(&client.address, &recipient, &1)
+            .linear_vest_amount,
+        3000
+    );
+    assert_eq!(
+        reader
+            .get_recipient_vesting_by_index(&client.address, &other_recipient, &0)
+            .linear_vest_amount,
+        2000
+    );
+
+    assert!(reader
+        .try_get_recipient_vesting_by_index(&client.address, &recipient, &2)
+        .is_err());
+    assert!(reader
+        .try_get_recipient_vesting_by_index(&client.address, &Address::generate(&env), &0)
+        .is_err());
+}
+
+#[test]
+fn test_get_allocations_in_window() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = start_timestamp + 200;
+    let initial_unlock: i128 = 100;
+    let cliff_amount: i128 = 200;
+    let linear_vest_amount: i128 = 800;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 2));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 2),
+        &expiration_ledger,
+    );
+
+    let vesting_id: u64 = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+
+    // Part of the linear ramp: 80 intervals of 10 tokens, 20 of them within the window.
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &(start_timestamp + 400),
+            &(start_timestamp + 600),
+            &0,
+            &1
+        ),
+        200
+    );
+
+    // The window start is inclusive and its end exclusive: the cliff amount vests at the
+    // cliff release timestamp and the last interval at the end timestamp.
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &start_timestamp,
+            &cliff_release_timestamp,
+            &0,
+            &1
+        ),
+        initial_unlock
+    );
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &cliff_release_timestamp,
+            &end_timestamp,
+            &0,
+            &1
+        ),
+        cliff_amount + linear_vest_amount - 10
+    );
+    assert_eq!(
+        reader.get_allocations_in_window(&client.address, &0, &u64::MAX, &0, &1),
+        total_expected_amount
+    );
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &(start_timestamp + 600),
+            &(start_timestamp + 400),
+            &0,
+            &1
+        ),
+        0
+    );
+
+    // Every vesting of the range is summed.
+    client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &timelock,
+        &initial_unlock,
+        &cliff_release_timestamp,
+        &cliff_amount,
+        &release_interval_secs,
+        &linear_vest_amount,
+    );
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &(start_timestamp + 400),
+            &(start_timestamp + 600),
+            &0,
+            &2
+        ),
+        400
+    );
+
+    // A revoked vesting no longer vests after its deactivation, the interval released at the
+    // deactivation timestamp included.
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.revoke_vesting(&admin, &vesting_id);
+    assert_eq!(
+        reader.get_allocations_in_window(
+            &client.address,
+            &(start_timestamp + 400),
+            &(start_timestamp + 600),
+            &0,
+            &1
+        ),
+        110
+    );
+}
+
+#[test]
+fn test_get_recipient_bundle() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let timelock: u64 = 0;
+    let release_interval_secs: u64 = 10;
+    let cliff_release_timestamp: u64 = 0;
+    let initial_unlock: i128 = 1000;
+    let cliff_amount: i128 = 0;
+    let linear_vest_amount: i128 = 1000;
+
+    let total_expected_amount: i128 = initial_unlock + cliff_amount + linear_vest_amount;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(total_expected_amount * 3));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(total_expected_amount * 3),
+        &expiration_ledger,
+    );
+
+    let mut vesting_ids: Vec<u64> = Vec::new(&env);
+    for _ in 0..3 {
+        vesting_ids.push_back(client.create_vesting(
+            &admin,
+            &recipient,
+            &start_timestamp,
+            &end_timestamp,
+            &timelock,
+            &initial_unlock,
+            &cliff_release_timestamp,
+            &cliff_amount,
+            &release_interval_secs,
+            &linear_vest_amount,
+        ));
+    }
+
+    env.ledger().set_timestamp(start_timestamp + 500);
+    client.claim(&recipient, &vesting_ids.get(0).unwrap());
+    client.revoke_vesting(&admin, &vesting_ids.get(1).unwrap());
+
+    let reference_timestamp = start_timestamp + 600;
+    let (bundle_ids, statuses, total_claimable) =
+        reader.get_recipient_bundle(&client.address, &recipient, &reference_timestamp, &0, &10);
+
+    assert_eq!(bundle_ids, vesting_ids);
+    assert_eq!(statuses.len(), 3);
+
+    let claimed_status = statuses.get(0).unwrap();
+    assert_eq!(claimed_status.status, 0);
+    assert_eq!(claimed_status.vested_amount, 1600);
+    assert_eq!(claimed_status.claimed_amount, 1500);
+    assert_eq!(claimed_status.claimable_amount, 100);
+
+    let revoked_status = statuses.get(1).unwrap();
+    assert_eq!(revoked_status.status, 1);
+    assert_eq!(
+        revoked_status.claimable_amount,
+        client.get_claimable_amount(&vesting_ids.get(1).unwrap(), &reference_timestamp)
+    );
+
+    let untouched_status = statuses.get(2).unwrap();
+    assert_eq!(untouched_status.status, 0);
+    assert_eq!(untouched_status.vested_amount, 1600);
+    assert_eq!(untouched_status.claimed_amount, 0);
+    assert_eq!(untouched_status.claimable_amount, 1600);
+
+    assert_eq!(
+        total_claimable,
+        claimed_status.claimable_amount
+            + revoked_status.claimable_amount
+            + untouched_status.claimable_amount
+    );
+
+    // Bundles are paged over the vesting list of the recipient.
+    let (bundle_ids, statuses, _) =
+        reader.get_recipient_bundle(&client.address, &recipient, &reference_timestamp, &1, &3);
+    assert_eq!(bundle_ids, vesting_ids.slice(1..3));
+    assert_eq!(statuses.get(0).unwrap(), revoked_status);
+
+    // An unknown recipient gets an empty bundle.
+    let (bundle_ids, statuses, total_claimable) = reader.get_recipient_bundle(
+        &client.address,
+        &Address::generate(&env),
+        &reference_timestamp,
+        &0,
+        &10,
+    );
+    assert!(bundle_ids.is_empty());
+    assert!(statuses.is_empty());
+    assert_eq!(total_claimable, 0);
+}
+
+#[test]
+fn test_claim_would_complete() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let recipient: Address = Address::generate(&env);
+    let start_timestamp: u64 = 1000;
+    let end_timestamp: u64 = start_timestamp + 1000;
+    let initial_unlock: i128 = 100;
+    let linear_vest_amount: i128 = 1000;
+    let expiration_ledger: u32 = 6300000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&admin, &(initial_unlock + linear_vest_amount));
+    token_client.approve(
+        &admin,
+        &client.address,
+        &(initial_unlock + linear_vest_amount),
+        &expiration_ledger,
+    );
+
+    let vesting_id = client.create_vesting(
+        &admin,
+        &recipient,
+        &start_timestamp,
+        &end_timestamp,
+        &0,
+        &initial_unlock,
+        &0,
+        &0,
+        &10,
+        &linear_vest_amount,
+    );
+
+    assert!(!reader.claim_would_complete(&client.address, &vesting_id, &(start_timestamp - 1)));
+    assert!(!reader.claim_would_complete(&client.address, &vesting_id, &(start_timestamp + 500)));
+    assert!(!reader.claim_would_complete(&client.address, &vesting_id, &(end_timestamp - 1)));
+    assert!(reader.claim_would_complete(&client.address, &vesting_id, &end_timestamp));
+    assert!(reader.claim_would_complete(&client.address, &vesting_id, &(end_timestamp + 1000)));
+
+    // Once drained there is nothing left to complete.
+    env.ledger().set_timestamp(end_timestamp);
+    client.claim(&recipient, &vesting_id);
+    assert!(!reader.claim_would_complete(&client.address, &vesting_id, &(end_timestamp + 1000)));
+}
+
+#[test]
+fn test_action_hashes_accepted_by_manager() {
+    let env = Env::default();
+    let (client, admin, token_client, token_admin_client, _) = deploy_manager_helper(&env);
+    let reader = deploy_reader_helper(&env);
+
+    let amount: i128 = 1000;
+
+    // Mock the admin.
+    env.mock_all_auths();
+    token_admin_client.mint(&client.address, &amount);
+
+    let second_admin: Address = Address::generate(&env);
+    client.set_admin(&admin, &second_admin, &true);
+    client.set_action_threshold(&admin, &2, &100);
+
+    let action_hash = reader.get_withdraw_action_hash(&admin, &amount);
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&second_admin, &action_hash);
+
+    client.withdraw_admin(&admin, &amount);
+    assert_eq!(token_client.balance(&admin), amount);
+
+    let third_admin: Address = Address::generate(&env);
+    let action_hash = reader.get_set_admin_action_hash(&admin, &third_admin, &true);
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&second_admin, &action_hash);
+
+    client.set_admin(&admin, &third_admin, &true);
+    assert!(client.is_admin(&third_admin));
+
+    let action_hash = reader.get_threshold_action_hash(&admin, &1, &100);
+    client.propose_action(&admin, &action_hash);
+    client.propose_action(&third_admin, &action_hash);
+
+    client.set_action_threshold(&admin, &1, &100);
+    assert_eq!(client.get_action_threshold(), 1);
+}